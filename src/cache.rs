@@ -0,0 +1,646 @@
+//! Freshness here has never depended on file mtime: each row carries its own
+//! `fetched_at` column (and, since the ETag support landed, an `etag`
+//! column alongside it), so restoring `quotes.db` from a backup or syncing
+//! it between machines doesn't disturb age calculations the way a bare
+//! mtime-keyed cache would. The one remaining mtime read is in
+//! [`import_legacy_file`], which has no other timestamp to draw on since
+//! the pre-SQLite JSON format it's importing never recorded one itself.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::quote::Quote;
+
+/// `override_dir` (see `Config::cache_dir`) if set, else
+/// `$XDG_CACHE_HOME/stocker/`, falling back to `~/.cache/stocker/` when
+/// `XDG_CACHE_HOME` isn't set, creating it on first use.
+fn cache_dir(override_dir: Option<&Path>) -> PathBuf {
+    let dir = match override_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => dirs::cache_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("stocker"),
+    };
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+/// Opens the quote history database, creating its schema on first use.
+/// Every fetched quote is kept (not just the latest per ticker), so the same
+/// table doubles as a freshness cache and a queryable local history.
+/// Concurrent writers (e.g. two waybar outputs racing to cache the same
+/// ticker) are serialized by SQLite's own locking, which is what replaced
+/// the write-to-temp-then-rename dance the old per-ticker JSON files needed.
+///
+/// If `quotes.db` itself is corrupt -- truncated by a crash, or just not a
+/// SQLite file, e.g. after a disk-full write -- opening it fails with
+/// SQLite's "file is not a database" on every single invocation from then
+/// on. Rather than leave `stocker` permanently broken until someone notices
+/// and deletes it by hand, that specific failure is treated as a signal to
+/// discard the file and start over: a fresh, empty cache is a lot less
+/// surprising than a tool that can no longer fetch quotes at all. Any other
+/// failure (a permission error, a missing parent directory, a full disk) is
+/// surfaced to the caller instead -- see [`is_not_a_database`].
+fn open(override_dir: Option<&Path>) -> Result<Connection> {
+    let dir = cache_dir(override_dir);
+    let path = dir.join("quotes.db");
+    match open_at(&path) {
+        Ok(conn) => {
+            migrate_legacy_files(&conn, &dir);
+            Ok(conn)
+        }
+        Err(err) if is_not_a_database(&err) => {
+            eprintln!(
+                "stocker: {} isn't a valid SQLite file, starting a fresh cache: {err:#}",
+                path.display()
+            );
+            let _ = fs::remove_file(&path);
+            let conn = open_at(&path)?;
+            migrate_legacy_files(&conn, &dir);
+            Ok(conn)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Whether `err` (from `open_at`) is SQLite's "file is not a database" --
+/// the one failure mode [`open`]'s self-heal is meant for. Anything else
+/// (disk full during `CREATE TABLE`, a permission error, a missing parent
+/// directory) is a transient or environmental problem, not corruption, and
+/// deleting a good cache wouldn't fix it -- so it's left alone and returned
+/// to the caller instead.
+fn is_not_a_database(err: &anyhow::Error) -> bool {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<rusqlite::Error>())
+        .and_then(rusqlite::Error::sqlite_error_code)
+        .is_some_and(|code| code == rusqlite::ErrorCode::NotADatabase)
+}
+
+fn open_at(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path)
+        .with_context(|| format!("opening cache database {}", path.display()))?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS quotes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ticker TEXT NOT NULL,
+            last REAL NOT NULL,
+            prev_close REAL NOT NULL,
+            fetched_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS quotes_ticker_fetched_at
+            ON quotes (ticker, fetched_at DESC);",
+    )
+    .context("creating cache database schema")?;
+    // Added after the table above already shipped, so existing databases
+    // need it bolted on; SQLite has no `ADD COLUMN IF NOT EXISTS`, so just
+    // ignore the "duplicate column" error a second run of this produces.
+    let _ = conn.execute("ALTER TABLE quotes ADD COLUMN etag TEXT", []);
+    let _ = conn.execute("ALTER TABLE quotes ADD COLUMN after_hours REAL", []);
+    let _ = conn.execute("ALTER TABLE quotes ADD COLUMN open REAL", []);
+    let _ = conn.execute("ALTER TABLE quotes ADD COLUMN volume REAL", []);
+    let _ = conn.execute("ALTER TABLE quotes ADD COLUMN day_high REAL", []);
+    let _ = conn.execute("ALTER TABLE quotes ADD COLUMN day_low REAL", []);
+    let _ = conn.execute("ALTER TABLE quotes ADD COLUMN bid REAL", []);
+    let _ = conn.execute("ALTER TABLE quotes ADD COLUMN ask REAL", []);
+    let _ = conn.execute("ALTER TABLE quotes ADD COLUMN last_trade_time TEXT", []);
+    let _ = conn.execute("ALTER TABLE quotes ADD COLUMN source TEXT", []);
+    let _ = conn.execute("ALTER TABLE quotes ADD COLUMN last_class TEXT", []);
+    Ok(conn)
+}
+
+/// Older versions of this crate cached quotes as JSON files instead of in
+/// this database: `cache_<ticker>.json` dropped in whatever directory the
+/// process was started from, and later `<ticker>.json` under the XDG cache
+/// directory. Runs once per process: imports any such file found as a row
+/// (using its mtime as `fetched_at`) and removes it, so a leftover file from
+/// before this crate moved to SQLite doesn't linger unused.
+fn migrate_legacy_files(conn: &Connection, dir: &Path) {
+    static MIGRATED: Once = Once::new();
+    MIGRATED.call_once(|| {
+        if let Ok(entries) = fs::read_dir(".") {
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                let Some(ticker) = name
+                    .to_str()
+                    .and_then(|name| name.strip_prefix("cache_"))
+                    .and_then(|name| name.strip_suffix(".json"))
+                else {
+                    continue;
+                };
+                if import_legacy_file(conn, &entry.path(), ticker) {
+                    let _ = fs::remove_file(entry.path());
+                }
+            }
+        }
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_none_or(|ext| ext != "json") {
+                    continue;
+                }
+                let Some(ticker) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                if import_legacy_file(conn, &path, ticker) {
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+    });
+}
+
+fn import_legacy_file(conn: &Connection, path: &Path, ticker: &str) -> bool {
+    let Ok(meta) = fs::metadata(path) else {
+        return false;
+    };
+    let fetched_at = meta
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |age| age.as_secs() as i64);
+    let Ok(raw) = fs::read_to_string(path) else {
+        return false;
+    };
+    let Ok(quote) = serde_json::from_str::<Quote>(&raw) else {
+        return false;
+    };
+    conn.execute(
+        "INSERT INTO quotes (ticker, last, prev_close, fetched_at) VALUES (?1, ?2, ?3, ?4)",
+        params![ticker, quote.last, quote.prev_close, fetched_at],
+    )
+    .is_ok()
+}
+
+/// The `ETag` header from whichever provider last successfully fetched
+/// `ticker`, if any -- ignoring `ttl` entirely, since a stale ETag is still
+/// useful to send as `If-None-Match` on the next refresh. `None` both when
+/// nothing is cached yet and when the last fetch's provider didn't return
+/// an ETag.
+pub fn read_etag(override_dir: Option<&Path>, ticker: &str) -> Option<String> {
+    let conn = open(override_dir).ok()?;
+    conn.query_row(
+        "SELECT etag FROM quotes WHERE ticker = ?1 ORDER BY fetched_at DESC LIMIT 1",
+        params![ticker],
+        |row| row.get(0),
+    )
+    .optional()
+    .ok()?
+}
+
+/// The threshold class (`"critdown"`, `"down"`, etc.) `ticker` was last
+/// rendered as, if any -- fed back into `output::class_with_hysteresis` on
+/// the next render so a ticker hovering at a boundary doesn't flip classes
+/// every refresh. `None` both when nothing is cached yet and when no render
+/// has recorded a class for it (e.g. hysteresis was off at the time).
+pub fn read_class(override_dir: Option<&Path>, ticker: &str) -> Option<String> {
+    let conn = open(override_dir).ok()?;
+    conn.query_row(
+        "SELECT last_class FROM quotes WHERE ticker = ?1 ORDER BY fetched_at DESC LIMIT 1",
+        params![ticker],
+        |row| row.get(0),
+    )
+    .optional()
+    .ok()?
+}
+
+/// Records `class` as `ticker`'s most recent threshold classification, for
+/// the next call's `read_class` -- stamped onto the latest history row
+/// rather than inserted as a new one, since this is metadata about how a
+/// quote was displayed, not a new quote itself.
+pub fn write_class(override_dir: Option<&Path>, ticker: &str, class: &str) -> Result<()> {
+    let conn = open(override_dir)?;
+    conn.execute(
+        "UPDATE quotes SET last_class = ?1 \
+         WHERE id = (SELECT id FROM quotes WHERE ticker = ?2 ORDER BY fetched_at DESC LIMIT 1)",
+        params![class, ticker],
+    )
+    .context("recording last threshold class")?;
+    Ok(())
+}
+
+type CachedRow = (
+    f64,
+    f64,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<String>,
+    Option<String>,
+    i64,
+);
+
+/// Returns the most recently fetched quote for `ticker` and how long ago it
+/// was fetched, if that's still within `ttl`. `ttl` is resolved by the
+/// caller from `Config::cache_ttl_for`, which knows about the schedule
+/// table, weekday/weekend split, and daily-only tickers.
+pub fn read(override_dir: Option<&Path>, ticker: &str, ttl: Duration) -> Option<(Quote, Duration)> {
+    let conn = open(override_dir).ok()?;
+    let (
+        last,
+        prev_close,
+        after_hours,
+        open,
+        volume,
+        day_high,
+        day_low,
+        bid,
+        ask,
+        last_trade_time,
+        source,
+        fetched_at,
+    ): CachedRow = conn
+        .query_row(
+            "SELECT last, prev_close, after_hours, open, volume, day_high, day_low, bid, ask, \
+             last_trade_time, source, fetched_at \
+             FROM quotes WHERE ticker = ?1 ORDER BY fetched_at DESC LIMIT 1",
+            params![ticker],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                    row.get(6)?,
+                    row.get(7)?,
+                    row.get(8)?,
+                    row.get(9)?,
+                    row.get(10)?,
+                    row.get(11)?,
+                ))
+            },
+        )
+        .optional()
+        .ok()??;
+    let fetched_at = UNIX_EPOCH + Duration::from_secs(fetched_at.max(0) as u64);
+    let age = SystemTime::now().duration_since(fetched_at).ok()?;
+    if age > ttl {
+        return None;
+    }
+    Some((
+        Quote {
+            ticker: ticker.to_string(),
+            last,
+            prev_close,
+            after_hours,
+            open,
+            volume,
+            day_high,
+            day_low,
+            bid,
+            ask,
+            last_trade_time,
+            source,
+        },
+        age,
+    ))
+}
+
+/// Appends `quote` to the history with the current time as its `fetched_at`,
+/// alongside the response `etag` (if the provider that fetched it sent one)
+/// so the next refresh can send it back as `If-None-Match`.
+pub fn write(override_dir: Option<&Path>, quote: &Quote, etag: Option<&str>) -> Result<()> {
+    let conn = open(override_dir)?;
+    let fetched_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    conn.execute(
+        "INSERT INTO quotes (ticker, last, prev_close, after_hours, open, volume, day_high, day_low, bid, ask, last_trade_time, source, fetched_at, etag) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![
+            quote.ticker,
+            quote.last,
+            quote.prev_close,
+            quote.after_hours,
+            quote.open,
+            quote.volume,
+            quote.day_high,
+            quote.day_low,
+            quote.bid,
+            quote.ask,
+            quote.last_trade_time,
+            quote.source,
+            fetched_at,
+            etag
+        ],
+    )
+    .context("inserting quote into cache database")?;
+    Ok(())
+}
+
+type TouchedRow = (
+    f64,
+    f64,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+/// Re-appends `ticker`'s most recently cached quote with the current time,
+/// leaving its price and etag untouched -- used when a conditional refresh
+/// comes back HTTP 304, so the cache counts as fresh again without a new
+/// quote to store.
+pub fn touch(override_dir: Option<&Path>, ticker: &str) -> Result<()> {
+    let conn = open(override_dir)?;
+    let Some((
+        last,
+        prev_close,
+        after_hours,
+        open,
+        volume,
+        day_high,
+        day_low,
+        bid,
+        ask,
+        last_trade_time,
+        source,
+        etag,
+    )): Option<TouchedRow> = conn
+        .query_row(
+            "SELECT last, prev_close, after_hours, open, volume, day_high, day_low, bid, ask, \
+             last_trade_time, source, etag \
+             FROM quotes WHERE ticker = ?1 ORDER BY fetched_at DESC LIMIT 1",
+            params![ticker],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                    row.get(6)?,
+                    row.get(7)?,
+                    row.get(8)?,
+                    row.get(9)?,
+                    row.get(10)?,
+                    row.get(11)?,
+                ))
+            },
+        )
+        .optional()
+        .context("reading cached quote to touch")?
+    else {
+        return Ok(());
+    };
+    let fetched_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    conn.execute(
+        "INSERT INTO quotes (ticker, last, prev_close, after_hours, open, volume, day_high, day_low, bid, ask, last_trade_time, source, fetched_at, etag) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![
+            ticker,
+            last,
+            prev_close,
+            after_hours,
+            open,
+            volume,
+            day_high,
+            day_low,
+            bid,
+            ask,
+            last_trade_time,
+            source,
+            fetched_at,
+            etag
+        ],
+    )
+    .context("re-stamping cached quote as fresh")?;
+    Ok(())
+}
+
+/// Deletes every stored quote for `ticker`, so the next `read` call misses
+/// and re-fetches, returning how many history rows were removed — used by
+/// the daemon's `refresh` control command and `stocker cache clear TICKER`.
+pub fn forget(override_dir: Option<&Path>, ticker: &str) -> Result<usize> {
+    let conn = open(override_dir)?;
+    conn.execute("DELETE FROM quotes WHERE ticker = ?1", params![ticker])
+        .context("deleting cached quotes")
+}
+
+/// Deletes every stored quote for every ticker, returning how many distinct
+/// tickers had at least one entry removed.
+pub fn clear(override_dir: Option<&Path>) -> Result<usize> {
+    let conn = open(override_dir)?;
+    let removed: i64 = conn.query_row("SELECT COUNT(DISTINCT ticker) FROM quotes", [], |row| {
+        row.get(0)
+    })?;
+    conn.execute("DELETE FROM quotes", [])
+        .context("clearing cache database")?;
+    Ok(removed as usize)
+}
+
+/// Deletes every cached row whose fetch is older than `older_than`,
+/// returning how many rows were removed. Tickers dropped from the config
+/// stop getting new rows and simply age out this way instead of
+/// accumulating in the database forever.
+pub fn gc(override_dir: Option<&Path>, older_than: Duration) -> Result<usize> {
+    let conn = open(override_dir)?;
+    let cutoff = SystemTime::now()
+        .checked_sub(older_than)
+        .unwrap_or(UNIX_EPOCH)
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    conn.execute("DELETE FROM quotes WHERE fetched_at < ?1", params![cutoff])
+        .context("garbage-collecting old cached quotes")
+}
+
+/// Every distinct ticker with a cached quote, its last known price, and how
+/// long ago that quote was fetched — for `stocker cache ls`.
+pub fn list(override_dir: Option<&Path>) -> Result<Vec<(String, f64, Duration)>> {
+    let conn = open(override_dir)?;
+    let mut stmt = conn
+        .prepare("SELECT ticker, last, MAX(fetched_at) FROM quotes GROUP BY ticker ORDER BY ticker")
+        .context("preparing cache listing query")?;
+    let now = SystemTime::now();
+    let rows = stmt
+        .query_map([], |row| {
+            let ticker: String = row.get(0)?;
+            let last: f64 = row.get(1)?;
+            let fetched_at: i64 = row.get(2)?;
+            Ok((ticker, last, fetched_at))
+        })
+        .context("listing cached quotes")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("reading cache listing rows")?;
+    Ok(rows
+        .into_iter()
+        .map(|(ticker, last, fetched_at)| {
+            let fetched_at = UNIX_EPOCH + Duration::from_secs(fetched_at.max(0) as u64);
+            let age = now.duration_since(fetched_at).unwrap_or_default();
+            (ticker, last, age)
+        })
+        .collect())
+}
+
+/// Every `last` price recorded for `ticker` since `since`, oldest first --
+/// the raw material for a sparkline of the day's price action. Since every
+/// fetch is kept as its own row (see the module doc comment), this is just a
+/// range scan; no separate intraday-samples table was needed.
+pub fn history_since(
+    override_dir: Option<&Path>,
+    ticker: &str,
+    since: SystemTime,
+) -> Result<Vec<f64>> {
+    let conn = open(override_dir)?;
+    let cutoff = since
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let mut stmt = conn
+        .prepare(
+            "SELECT last FROM quotes WHERE ticker = ?1 AND fetched_at >= ?2 \
+             ORDER BY fetched_at ASC",
+        )
+        .context("preparing cache history query")?;
+    let prices = stmt
+        .query_map(params![ticker, cutoff], |row| row.get(0))
+        .context("querying cached quote history")?
+        .collect::<rusqlite::Result<Vec<f64>>>()
+        .context("reading cached quote history rows")?;
+    Ok(prices)
+}
+
+struct MemEntry {
+    quote: Quote,
+    fetched_at: SystemTime,
+    etag: Option<String>,
+    /// Set on every write, cleared once [`MemCache::flush`] has persisted the
+    /// entry, so a rotation tick that hits this ticker again before the next
+    /// flush doesn't re-write the same row to disk for nothing.
+    dirty: bool,
+    /// The threshold class this ticker was last rendered as, for
+    /// [`MemCache::read_class`]. Not written back by [`MemCache::flush`] --
+    /// like the rest of this cache, it just resets when the daemon restarts.
+    last_class: Option<String>,
+}
+
+/// An in-memory front for the on-disk cache, used by `stocker daemon` so a
+/// rotation tick that re-visits a ticker it already fetched this session
+/// doesn't pay for a SQLite round trip. Entries are only written to disk by
+/// an explicit [`MemCache::flush`], not on every [`MemCache::write`], so a
+/// long-running daemon does most of its cache traffic purely in memory.
+#[derive(Default)]
+pub struct MemCache {
+    entries: Mutex<HashMap<String, MemEntry>>,
+}
+
+impl MemCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mirrors [`read`], but against the in-memory map instead of SQLite.
+    pub fn read(&self, ticker: &str, ttl: Duration) -> Option<(Quote, Duration)> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(ticker)?;
+        let age = SystemTime::now().duration_since(entry.fetched_at).ok()?;
+        if age > ttl {
+            return None;
+        }
+        Some((entry.quote.clone(), age))
+    }
+
+    /// Mirrors [`read`] with an unbounded TTL, for the stale-while-revalidate
+    /// fallback -- returns the entry regardless of age.
+    pub fn read_stale(&self, ticker: &str) -> Option<(Quote, Duration)> {
+        self.read(ticker, Duration::MAX)
+    }
+
+    /// Mirrors [`read_etag`], but against the in-memory map instead of SQLite.
+    pub fn read_etag(&self, ticker: &str) -> Option<String> {
+        self.entries.lock().unwrap().get(ticker)?.etag.clone()
+    }
+
+    /// Records `quote` as freshly fetched, marking it dirty so the next
+    /// [`MemCache::flush`] persists it. Preserves any `last_class` already
+    /// recorded for this ticker, so a fresh fetch doesn't itself reset
+    /// hysteresis.
+    pub fn write(&self, quote: &Quote, etag: Option<&str>) {
+        let mut entries = self.entries.lock().unwrap();
+        let last_class = entries
+            .get(&quote.ticker)
+            .and_then(|e| e.last_class.clone());
+        entries.insert(
+            quote.ticker.clone(),
+            MemEntry {
+                quote: quote.clone(),
+                fetched_at: SystemTime::now(),
+                etag: etag.map(str::to_string),
+                dirty: true,
+                last_class,
+            },
+        );
+    }
+
+    /// Mirrors [`read_class`], but against the in-memory map instead of
+    /// SQLite.
+    pub fn read_class(&self, ticker: &str) -> Option<String> {
+        self.entries.lock().unwrap().get(ticker)?.last_class.clone()
+    }
+
+    /// Mirrors [`write_class`], but against the in-memory map instead of
+    /// SQLite. A no-op if `ticker` isn't in memory yet.
+    pub fn write_class(&self, ticker: &str, class: &str) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(ticker) {
+            entry.last_class = Some(class.to_string());
+        }
+    }
+
+    /// Drops `ticker` from memory, so the next read falls through to a fresh
+    /// network fetch -- used alongside [`forget`] for `stocker ctl refresh`.
+    pub fn forget(&self, ticker: &str) {
+        self.entries.lock().unwrap().remove(ticker);
+    }
+
+    /// Mirrors [`touch`]: re-stamps `ticker`'s entry as fresh without
+    /// changing its price or etag, for a conditional refresh that came back
+    /// 304. A no-op if `ticker` isn't in memory yet.
+    pub fn touch(&self, ticker: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries.get_mut(ticker) {
+            entry.fetched_at = SystemTime::now();
+            entry.dirty = true;
+        }
+    }
+
+    /// Persists every dirty entry to the on-disk cache and clears their
+    /// dirty flags. Cheap to call often -- a daemon idling between rotations
+    /// with nothing new to write does no database work at all.
+    pub fn flush(&self, override_dir: Option<&Path>) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        for entry in entries.values_mut() {
+            if !entry.dirty {
+                continue;
+            }
+            // Stamped with the flush time rather than `entry.fetched_at` --
+            // the row is written late, but only by up to one flush interval,
+            // which is a fine trade for skipping a database write per tick.
+            write(override_dir, &entry.quote, entry.etag.as_deref())?;
+            entry.dirty = false;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand};
+
+/// A status-bar quote fetcher for stocks, crypto, FX, and funds.
+#[derive(Parser)]
+#[command(name = "stocker")]
+pub struct Cli {
+    /// Path to the config file. Defaults to `$XDG_CONFIG_HOME/stocker/config.toml`.
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Output format: waybar, polybar, xmobar, plain, lemonbar, dzen2,
+    /// i3bar, swaybar, csv, or prometheus.
+    #[arg(long, global = true, default_value = "waybar")]
+    pub format: String,
+
+    /// Shorthand for `--format plain`.
+    #[arg(long, global = true)]
+    pub plain: bool,
+
+    /// Colorize plain-text output.
+    #[arg(long, global = true)]
+    pub color: bool,
+
+    /// Print what stocker is doing (which provider, cache hits/misses) to stderr.
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+
+    /// Use a named watchlist from `[watchlists]` instead of the default `tickers` list.
+    #[arg(long, global = true)]
+    pub watchlist: Option<String>,
+
+    /// Force a single symbol for this run instead of the configured
+    /// watchlist, e.g. for a sway keybinding that pops up one quote on demand.
+    /// Only takes effect when no subcommand is given (`stocker --ticker TSLA`);
+    /// subcommands, `quote` included, ignore it.
+    #[arg(long, global = true)]
+    pub ticker: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Fetch and print quotes (the default when no subcommand is given).
+    Quote {
+        /// Fetch just this ticker instead of the configured watchlist.
+        ticker: Option<String>,
+    },
+    /// Print the tickers configured in the watchlist.
+    List,
+    /// Manage the config file.
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+    /// Manage the on-disk quote cache.
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommand,
+    },
+    /// Fetch and cache every ticker in the active watchlist, without
+    /// printing a quote for any of them. Meant to run from a systemd timer
+    /// or a login script so the bar's first real invocation is already a
+    /// cache hit instead of a cold fetch.
+    Prefetch,
+    /// Open the interactive TUI watchlist.
+    Tui,
+    /// Stay resident and print one line of output per tick instead of
+    /// exiting after one, so waybar's `exec` module doesn't have to spawn a
+    /// fresh process (and re-do TLS handshakes) every `rotation_seconds`.
+    Daemon,
+    /// Send a control command to a running `stocker daemon`.
+    Ctl {
+        #[command(subcommand)]
+        action: CtlCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CtlCommand {
+    /// Advance the running daemon to the next ticker in its watchlist.
+    Next,
+    /// Go back to the previous ticker in its watchlist.
+    Prev,
+    /// Force the running daemon to refetch instead of waiting out the cache TTL.
+    Refresh,
+    /// Jump the running daemon straight to this ticker.
+    Show {
+        /// The ticker's symbol, as it appears in the watchlist.
+        ticker: String,
+    },
+    /// Toggle automatic rotation in the running daemon.
+    Pause,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Parse the config file and report whether it's valid.
+    Validate {
+        /// Also fetch each configured ticker to confirm the API key and
+        /// provider actually work, not just that the file parses.
+        #[arg(long)]
+        check_provider: bool,
+    },
+    /// Write a commented starter config to get a new setup running quickly.
+    Init {
+        /// Overwrite the config file if one already exists.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print a JSON Schema for the config format, for editor autocompletion.
+    Schema,
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommand {
+    /// List cached tickers with their last price and how long ago it was fetched.
+    Ls,
+    /// Delete cached quotes for one ticker, or every ticker if none is given.
+    Clear {
+        /// Only clear this ticker's cache; clears everything if omitted.
+        ticker: Option<String>,
+    },
+    /// Delete cached quotes older than a given age, e.g. `7d`, `12h`, `30m`.
+    Gc {
+        /// A number followed by a single unit: `s`, `m`, `h`, `d`, or `w`.
+        #[arg(long, value_parser = parse_duration)]
+        older_than: Duration,
+    },
+}
+
+/// Parses a simple `<number><unit>` duration like `7d` or `30m` for
+/// `--older-than`. Doesn't support combined units (`1d12h`) -- there's no
+/// need for that precision when garbage-collecting a quote cache.
+fn parse_duration(input: &str) -> Result<Duration, String> {
+    let unit_len = input
+        .chars()
+        .last()
+        .filter(|c| c.is_alphabetic())
+        .map_or(0, char::len_utf8);
+    if unit_len == 0 {
+        return Err(format!(
+            "invalid duration {input:?}: expected a number followed by s/m/h/d/w, e.g. \"7d\""
+        ));
+    }
+    let (amount, unit) = input.split_at(input.len() - unit_len);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("invalid duration {input:?}: {amount:?} isn't a number"))?;
+    let seconds = match unit {
+        "s" => amount,
+        "m" => amount * 60,
+        "h" => amount * 60 * 60,
+        "d" => amount * 60 * 60 * 24,
+        "w" => amount * 60 * 60 * 24 * 7,
+        other => {
+            return Err(format!(
+                "invalid duration {input:?}: unknown unit {other:?} (expected s/m/h/d/w)"
+            ))
+        }
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_supports_each_unit() {
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("7d"), Ok(Duration::from_secs(604_800)));
+        assert_eq!(parse_duration("1w"), Ok(Duration::from_secs(604_800)));
+    }
+
+    #[test]
+    fn parse_duration_rejects_missing_unit_or_number() {
+        assert!(parse_duration("7").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("7x").is_err());
+    }
+}
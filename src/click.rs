@@ -0,0 +1,87 @@
+use std::io::{self, BufRead};
+use std::process::Command;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use serde::Deserialize;
+
+use crate::ipc::DaemonState;
+use crate::output;
+
+/// Waybar's button numbers, per its `custom` module documentation. Scroll
+/// events reuse the X11 convention of reporting the wheel as buttons 4/5.
+const LEFT: u8 = 1;
+const MIDDLE: u8 = 2;
+const RIGHT: u8 = 3;
+const SCROLL_UP: u8 = 4;
+const SCROLL_DOWN: u8 = 5;
+/// The "back" side button some mice report as button 8. Not part of
+/// waybar's documented set, but nothing else claims it, so it's free to use
+/// for a rarely-needed action like pausing.
+const TOGGLE_PAUSE: u8 = 8;
+
+/// The subset of waybar's click-event JSON (written to a module's stdin
+/// when the module sets `"return-type": "json"`) that stocker cares about.
+#[derive(Debug, Deserialize)]
+struct ClickEvent {
+    button: u8,
+}
+
+/// Starts a background thread that reads waybar/i3bar click-event JSON
+/// lines from stdin, turning them into the same daemon state changes a
+/// `stocker ctl` command would make: left click or scroll-down advances to
+/// the next ticker, scroll-up goes back, middle click forces a refresh,
+/// right click opens `click_url_template` (if set) in a browser, and the
+/// side "back" button toggles pause (see `stocker ctl pause`).
+pub fn watch(state: Arc<Mutex<DaemonState>>, condvar: Arc<Condvar>, url_template: Option<String>) {
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines().map_while(Result::ok) {
+            let Ok(event) = serde_json::from_str::<ClickEvent>(&line) else {
+                continue;
+            };
+            match event.button {
+                LEFT | SCROLL_DOWN => step(&state, &condvar, 1),
+                SCROLL_UP => step(&state, &condvar, -1),
+                MIDDLE => {
+                    let mut state = state.lock().unwrap();
+                    state.force_refresh = true;
+                    state.wake = true;
+                    condvar.notify_one();
+                }
+                RIGHT => open_current_ticker(&state, url_template.as_deref()),
+                TOGGLE_PAUSE => toggle_pause(&state, &condvar),
+                _ => {}
+            }
+        }
+    });
+}
+
+/// Moves the shown ticker by `delta` positions and wakes the rotation loop,
+/// the same way `next`/`prev` over the control socket do.
+fn step(state: &Arc<Mutex<DaemonState>>, condvar: &Arc<Condvar>, delta: i64) {
+    let mut state = state.lock().unwrap();
+    state.index = state.index.wrapping_add(delta);
+    state.wake = true;
+    condvar.notify_one();
+}
+
+/// Toggles automatic rotation, the same way `stocker ctl pause` does.
+fn toggle_pause(state: &Arc<Mutex<DaemonState>>, condvar: &Arc<Condvar>) {
+    let mut state = state.lock().unwrap();
+    state.paused = !state.paused;
+    state.wake = true;
+    condvar.notify_one();
+}
+
+fn open_current_ticker(state: &Arc<Mutex<DaemonState>>, url_template: Option<&str>) {
+    let Some(template) = url_template else {
+        return;
+    };
+    let Some(quote) = state.lock().unwrap().current_quote.clone() else {
+        return;
+    };
+    let Ok(url) = output::render_template(template, &quote) else {
+        return;
+    };
+    let _ = Command::new("xdg-open").arg(url).spawn();
+}
@@ -0,0 +1,1653 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::quote::Quote;
+
+/// Falls back to this TTL for a stock/crypto/FX ticker on a Saturday or
+/// Sunday, absent a matching `cache_schedule` rule.
+const DEFAULT_WEEKEND_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+/// Funds only publish a NAV once a day, so a daily-only ticker always uses
+/// this TTL regardless of `cache_schedule`.
+const DEFAULT_DAILY_TTL: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// The day of the week for `now`, Monday = 0 through Sunday = 6.
+fn weekday_index(now: SystemTime) -> u8 {
+    let days = now
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / 86_400;
+    // 1970-01-01 was a Thursday, i.e. index 3 when Monday = 0.
+    ((days as i64 + 3).rem_euclid(7)) as u8
+}
+
+/// Saturday/Sunday, used as the default "weekend" for `cache_ttl_for`'s
+/// final fallback when nothing overrides it -- see `Config::weekend_days`.
+const DEFAULT_WEEKEND_DAYS: [Weekday; 2] = [Weekday::Sat, Weekday::Sun];
+
+fn is_weekend(now: SystemTime, weekend_days: &[Weekday]) -> bool {
+    weekend_days
+        .iter()
+        .any(|day| day.index() == weekday_index(now))
+}
+
+/// Minutes since midnight UTC for `now`.
+fn minute_of_day(now: SystemTime) -> u32 {
+    let secs = now
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    ((secs % 86_400) / 60) as u32
+}
+
+/// A short, stable id for `path`, used as the cache subdirectory name for a
+/// non-default config file with no explicit `cache_namespace`. Canonicalized
+/// first so `--config ./foo.toml` and `--config /home/me/foo.toml` land in
+/// the same namespace when they name the same file.
+fn config_path_hash(path: &Path) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut hasher = DefaultHasher::new();
+    canonical.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn parse_hhmm(s: &str) -> Option<u32> {
+    let (hour, minute) = s.split_once(':')?;
+    Some(hour.parse::<u32>().ok()? * 60 + minute.parse::<u32>().ok()?)
+}
+
+/// NYSE/NASDAQ full-day closures through 2027, since the ones tied to a
+/// weekday rule (Thanksgiving, MLK Day, Good Friday, ...) rather than a
+/// fixed calendar date don't reduce to a formula worth deriving here, and a
+/// hardcoded table needs refreshing eventually anyway. Extend a config's
+/// `holidays` list for a market or year this table doesn't cover rather than
+/// waiting on a new release.
+const US_MARKET_HOLIDAYS: &[&str] = &[
+    "2024-01-01",
+    "2024-01-15",
+    "2024-02-19",
+    "2024-03-29",
+    "2024-05-27",
+    "2024-06-19",
+    "2024-07-04",
+    "2024-09-02",
+    "2024-11-28",
+    "2024-12-25",
+    "2025-01-01",
+    "2025-01-20",
+    "2025-02-17",
+    "2025-04-18",
+    "2025-05-26",
+    "2025-06-19",
+    "2025-07-04",
+    "2025-09-01",
+    "2025-11-27",
+    "2025-12-25",
+    "2026-01-01",
+    "2026-01-19",
+    "2026-02-16",
+    "2026-04-03",
+    "2026-05-25",
+    "2026-06-19",
+    "2026-07-03",
+    "2026-09-07",
+    "2026-11-26",
+    "2026-12-25",
+    "2027-01-01",
+    "2027-01-18",
+    "2027-02-15",
+    "2027-03-26",
+    "2027-05-31",
+    "2027-06-18",
+    "2027-07-05",
+    "2027-09-06",
+    "2027-11-25",
+    "2027-12-24",
+];
+
+/// NYSE/NASDAQ scheduled early closes (1pm ET) through 2027 -- the trading
+/// day before Independence Day, the day after Thanksgiving, and Christmas
+/// Eve, when not already a full holiday or a weekend. Like [`US_MARKET_HOLIDAYS`], extend a config's `early_closes` map
+/// for a market, year, or one-off closure this table doesn't cover.
+const US_MARKET_EARLY_CLOSES: &[(&str, &str)] = &[
+    ("2024-07-03", "17:00"),
+    ("2024-11-29", "18:00"),
+    ("2024-12-24", "18:00"),
+    ("2025-07-03", "17:00"),
+    ("2025-11-28", "18:00"),
+    ("2025-12-24", "18:00"),
+    ("2026-11-27", "18:00"),
+    ("2026-12-24", "18:00"),
+    ("2027-07-02", "17:00"),
+    ("2027-11-26", "18:00"),
+];
+
+/// Converts days-since-epoch to a proleptic Gregorian `(year, month, day)`,
+/// so `now` can be compared against the holiday table's `YYYY-MM-DD`
+/// strings without pulling in a full calendar dependency for ten lines of
+/// arithmetic. (Howard Hinnant's `civil_from_days`.)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = (z - era * 146_097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (if m <= 2 { y + 1 } else { y }, m, d)
+}
+
+fn date_string(now: SystemTime) -> String {
+    let days = now
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+        / 86_400;
+    let (y, m, d) = civil_from_days(days);
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+/// A day of the week, as written in a `cache_schedule` rule's `days` list.
+#[derive(Debug, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Weekday {
+    fn index(self) -> u8 {
+        match self {
+            Weekday::Mon => 0,
+            Weekday::Tue => 1,
+            Weekday::Wed => 2,
+            Weekday::Thu => 3,
+            Weekday::Fri => 4,
+            Weekday::Sat => 5,
+            Weekday::Sun => 6,
+        }
+    }
+}
+
+/// Which reference price `percent_change` is computed against, set via
+/// `Config::percent_change_baseline` or a `TickerEntry::Detailed`'s own
+/// `baseline`. See [`Config::baseline_price`] for how a ticker's
+/// `reference_price` fits in alongside this.
+#[derive(Debug, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PercentChangeBaseline {
+    PrevClose,
+    Open,
+}
+
+/// One rule in a `cache_schedule` table, e.g. an aggressive refresh window
+/// during market hours or a slow one overnight. Times are UTC, since the
+/// config format has no way to say what timezone a market's local hours are
+/// in; convert `start`/`end` to UTC yourself.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ScheduleRule {
+    pub days: Vec<Weekday>,
+    /// `"HH:MM"`, UTC. If omitted (along with `end`), the rule matches all
+    /// day on any listed `days`.
+    #[serde(default)]
+    pub start: Option<String>,
+    #[serde(default)]
+    pub end: Option<String>,
+    pub ttl_seconds: u64,
+}
+
+impl ScheduleRule {
+    fn matches(&self, now: SystemTime) -> bool {
+        if !self
+            .days
+            .iter()
+            .any(|day| day.index() == weekday_index(now))
+        {
+            return false;
+        }
+        let (Some(start), Some(end)) = (&self.start, &self.end) else {
+            return true;
+        };
+        let (Some(start), Some(end)) = (parse_hhmm(start), parse_hhmm(end)) else {
+            return true;
+        };
+        let minute = minute_of_day(now);
+        minute >= start && minute < end
+    }
+}
+
+/// A market's trading hours, used to hold the cache until the next open
+/// instead of polling all evening and overnight. Like `cache_schedule`,
+/// times are UTC -- convert your market's local hours yourself. Holidays
+/// (the built-in US market calendar, plus a config's `holidays` list) are
+/// treated as closed even on an otherwise-listed weekday; see
+/// [`Config::is_holiday`].
+///
+/// Also accepts a compact `"HH:MM-HH:MM"` string in place of the full table,
+/// for a plain Monday-Friday week -- e.g. `"08:00-16:30"` for the London
+/// Stock Exchange. A trailing `" <label>"`, as in `"08:00-16:30
+/// Europe/London"`, is accepted for readability but not converted: this
+/// crate has no IANA timezone database dependency, so both forms still
+/// expect UTC times.
+#[derive(Debug, JsonSchema)]
+pub struct MarketHours {
+    pub days: Vec<Weekday>,
+    /// `"HH:MM"`, UTC.
+    pub open: String,
+    /// `"HH:MM"`, UTC.
+    pub close: String,
+    /// `"HH:MM"`, UTC. If set, the window from here to `open` on a listed
+    /// day is [`MarketStatus::PreMarket`] instead of [`MarketStatus::Closed`].
+    #[serde(default)]
+    pub pre_market_open: Option<String>,
+    /// `"HH:MM"`, UTC. If set, the window from `close` to here on a listed
+    /// day is [`MarketStatus::AfterHours`] instead of [`MarketStatus::Closed`].
+    #[serde(default)]
+    pub after_hours_close: Option<String>,
+}
+
+impl<'de> serde::Deserialize<'de> for MarketHours {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Compact(String),
+            Table {
+                days: Vec<Weekday>,
+                open: String,
+                close: String,
+                #[serde(default)]
+                pre_market_open: Option<String>,
+                #[serde(default)]
+                after_hours_close: Option<String>,
+            },
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Table {
+                days,
+                open,
+                close,
+                pre_market_open,
+                after_hours_close,
+            } => Ok(MarketHours {
+                days,
+                open,
+                close,
+                pre_market_open,
+                after_hours_close,
+            }),
+            Repr::Compact(s) => parse_compact_market_hours(&s).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Parses the `"HH:MM-HH:MM"` shorthand documented on [`MarketHours`] into a
+/// full Monday-Friday table.
+fn parse_compact_market_hours(s: &str) -> std::result::Result<MarketHours, String> {
+    let range = s.split_whitespace().next().unwrap_or(s);
+    let (open, close) = range
+        .split_once('-')
+        .ok_or_else(|| format!("invalid trading hours {s:?}, expected \"HH:MM-HH:MM\""))?;
+    Ok(MarketHours {
+        days: vec![
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ],
+        open: open.to_string(),
+        close: close.to_string(),
+        pre_market_open: None,
+        after_hours_close: None,
+    })
+}
+
+/// A ticker's exchange session, as derived by [`MarketHours::status`], for
+/// formatters that want to show more than just whether the cache is being
+/// held (see `Options::market_status` in the `output` module).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketStatus {
+    PreMarket,
+    Open,
+    AfterHours,
+    Closed,
+}
+
+impl MarketStatus {
+    /// A short, lowercase, hyphenated label, e.g. for a CSS class.
+    pub fn label(self) -> &'static str {
+        match self {
+            MarketStatus::PreMarket => "pre-market",
+            MarketStatus::Open => "open",
+            MarketStatus::AfterHours => "after-hours",
+            MarketStatus::Closed => "closed",
+        }
+    }
+
+    /// Whether the session is heading towards its next `open` (`Closed`,
+    /// `PreMarket`) or its next `close` (`Open`, `AfterHours`) -- used to
+    /// pick between "opens in" and "closes in" wording.
+    pub fn heading_to_open(self) -> bool {
+        matches!(self, MarketStatus::Closed | MarketStatus::PreMarket)
+    }
+}
+
+impl MarketHours {
+    fn is_open(&self, now: SystemTime, is_holiday: bool, early_close: Option<u32>) -> bool {
+        if is_holiday {
+            return false;
+        }
+        let (Some(open), Some(mut close)) = (parse_hhmm(&self.open), parse_hhmm(&self.close))
+        else {
+            return true;
+        };
+        if let Some(early) = early_close {
+            close = close.min(early);
+        }
+        self.days
+            .iter()
+            .any(|day| day.index() == weekday_index(now))
+            && (open..close).contains(&minute_of_day(now))
+    }
+
+    /// The current session and how long until it next changes. Only looks
+    /// at same-day arithmetic for `PreMarket`/`Open`/`AfterHours`, falling
+    /// back to [`Self::duration_until_open`]'s day-by-day scan for `Closed`.
+    /// `early_close` shortens today's `close` (e.g. 1pm ET the day after
+    /// Thanksgiving) without touching `duration_until_open`'s scan, since an
+    /// early close never moves the next `open`.
+    pub fn status(
+        &self,
+        now: SystemTime,
+        is_holiday: &dyn Fn(SystemTime) -> bool,
+        early_close: &dyn Fn(SystemTime) -> Option<u32>,
+    ) -> (MarketStatus, Duration) {
+        let (Some(open), Some(mut close)) = (parse_hhmm(&self.open), parse_hhmm(&self.close))
+        else {
+            return (MarketStatus::Open, Duration::ZERO);
+        };
+        if let Some(early) = early_close(now) {
+            close = close.min(early);
+        }
+        let listed = !is_holiday(now)
+            && self
+                .days
+                .iter()
+                .any(|day| day.index() == weekday_index(now));
+        let minute = minute_of_day(now);
+        let until = |target: u32| Duration::from_secs((target.saturating_sub(minute) as u64) * 60);
+        if listed {
+            if (open..close).contains(&minute) {
+                return (MarketStatus::Open, until(close));
+            }
+            if let Some(pre_open) = self.pre_market_open.as_deref().and_then(parse_hhmm) {
+                if (pre_open..open).contains(&minute) {
+                    return (MarketStatus::PreMarket, until(open));
+                }
+            }
+            if let Some(after_close) = self.after_hours_close.as_deref().and_then(parse_hhmm) {
+                if (close..after_close).contains(&minute) {
+                    return (MarketStatus::AfterHours, until(after_close));
+                }
+            }
+        }
+        (
+            MarketStatus::Closed,
+            self.duration_until_open(now, is_holiday),
+        )
+    }
+
+    /// How long until the next `open`, scanning forward day by day (at most
+    /// two weeks, to leave room for a run of listed days that are all
+    /// holidays) rather than doing modular arithmetic on `days`, since it
+    /// can be an arbitrary, non-contiguous subset of the week.
+    fn duration_until_open(
+        &self,
+        now: SystemTime,
+        is_holiday: &dyn Fn(SystemTime) -> bool,
+    ) -> Duration {
+        let Some(open_minute) = parse_hhmm(&self.open) else {
+            return Duration::ZERO;
+        };
+        let now_secs = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let today_start = now_secs - (now_secs % 86_400);
+        for offset in 0..14 {
+            let day_start = today_start + offset * 86_400;
+            let day_time = SystemTime::UNIX_EPOCH + Duration::from_secs(day_start);
+            let day_index = weekday_index(day_time);
+            if !self.days.iter().any(|day| day.index() == day_index) || is_holiday(day_time) {
+                continue;
+            }
+            let open_at = day_start + open_minute as u64 * 60;
+            if open_at > now_secs {
+                return Duration::from_secs(open_at - now_secs);
+            }
+        }
+        Duration::from_secs(14 * 86_400)
+    }
+}
+
+/// The percent-change boundaries a quote is classified against. A 2% move
+/// means something different for a bond ETF than for a crypto pair, so
+/// these can be tuned per ticker instead of being one global constant.
+#[derive(Debug, Deserialize, JsonSchema, Clone, Copy)]
+pub struct Thresholds {
+    /// Below this, the quote is "critdown" instead of just "down".
+    #[serde(default = "Thresholds::default_critdown")]
+    pub critdown: f64,
+    /// Below this (and above `critdown`), the quote is "down".
+    #[serde(default = "Thresholds::default_down")]
+    pub down: f64,
+    /// Above this, the quote is "wayup" instead of just "up".
+    #[serde(default = "Thresholds::default_wayup")]
+    pub wayup: f64,
+    /// A move within this many percentage points of zero, in either
+    /// direction, is "flat" rather than "up"/"down" -- without this, a
+    /// barely-positive move like +0.01% still renders as "up", which reads
+    /// as misleadingly bullish. Defaults to 0.0 (only an exact 0.0% change
+    /// is "flat"), matching this crate's behavior before this setting
+    /// existed.
+    #[serde(default = "Thresholds::default_flat")]
+    pub flat: f64,
+    /// Below this -- more extreme than `critdown` -- the quote also picks up
+    /// a `"critical"` waybar class alongside the usual `"critdown"`, for
+    /// "drop everything and look" moves like an intraday -8%. Left unset by
+    /// default, since not every setup wants a second down tier or the CSS to
+    /// make it blink.
+    #[serde(default)]
+    pub critical: Option<f64>,
+    /// A percentage-point buffer around each boundary above: once a ticker
+    /// is classified into a class, the move must clear that class's boundary
+    /// by more than this margin before it's reclassified, so hovering right
+    /// at a threshold doesn't flip the class (and waybar's color) back and
+    /// forth every refresh. Defaults to 0.0, which disables hysteresis and
+    /// classifies purely off the raw thresholds, as before this setting
+    /// existed. See `output::class_with_hysteresis`.
+    #[serde(default = "Thresholds::default_hysteresis")]
+    pub hysteresis: f64,
+}
+
+impl Thresholds {
+    fn default_critdown() -> f64 {
+        -3.0
+    }
+
+    fn default_down() -> f64 {
+        0.0
+    }
+
+    fn default_wayup() -> f64 {
+        3.0
+    }
+
+    fn default_flat() -> f64 {
+        0.0
+    }
+
+    fn default_hysteresis() -> f64 {
+        0.0
+    }
+
+    /// Whether the thresholds are sane, i.e. `critdown < down < wayup`,
+    /// `flat` isn't negative, `critical` (if set) is more extreme than
+    /// `critdown`, and `hysteresis` isn't negative.
+    pub fn is_ordered(&self) -> bool {
+        self.critdown < self.down
+            && self.down < self.wayup
+            && self.flat >= 0.0
+            && self
+                .critical
+                .is_none_or(|critical| critical < self.critdown)
+            && self.hysteresis >= 0.0
+    }
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            critdown: Self::default_critdown(),
+            down: Self::default_down(),
+            wayup: Self::default_wayup(),
+            flat: Self::default_flat(),
+            hysteresis: Self::default_hysteresis(),
+            critical: None,
+        }
+    }
+}
+
+/// Replaces `${VAR_NAME}` in a config file's raw text with the value of the
+/// matching environment variable, so the same file works across machines
+/// with different secrets. An unset variable is left as-is rather than
+/// silently blanked, so a typo shows up in the parsed value instead of
+/// disappearing.
+fn interpolate_env(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                match env::var(name) {
+                    Ok(value) => out.push_str(&value),
+                    Err(_) => out.push_str(&rest[start..start + 2 + end + 1]),
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+                break;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// One entry in the ticker rotation. Most watchlists just need a plain
+/// symbol, but an entry can also pin its own backend and its own display
+/// knobs, letting a single rotation mix asset classes served by different
+/// providers with different volatility profiles.
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum TickerEntry {
+    Plain(String),
+    Detailed {
+        symbol: String,
+        provider: Option<String>,
+        #[serde(default)]
+        thresholds: Option<Box<Thresholds>>,
+        /// Overrides the top-level `precision` for just this ticker, e.g. 4
+        /// places for an FX pair alongside whole-dollar equities.
+        #[serde(default)]
+        precision: Option<usize>,
+        #[serde(default)]
+        display_name: Option<String>,
+        /// Overrides the top-level `cache_seconds` for just this ticker, e.g.
+        /// a shorter TTL for a volatile crypto pair or a longer one for a
+        /// bond ETF that barely moves intraday.
+        #[serde(default)]
+        cache_seconds: Option<u64>,
+        /// Overrides the top-level `market_hours` for just this ticker,
+        /// e.g. an ASX-listed stock alongside an NYSE-listed one in the
+        /// same watchlist, each closed on its own schedule. Still entered
+        /// in UTC like the top-level setting -- this crate has no IANA
+        /// timezone database dependency to convert a `"Asia/Tokyo"` or
+        /// `"Australia/Sydney"` name (or its DST rules) for you, so convert
+        /// the exchange's local hours yourself.
+        #[serde(default)]
+        market_hours: Option<Box<MarketHours>>,
+        /// Overrides the top-level `weekend_days` for just this ticker, e.g.
+        /// a Tel Aviv listing alongside an NYSE one in the same watchlist,
+        /// each with a different trading week.
+        #[serde(default)]
+        weekend_days: Option<Vec<Weekday>>,
+        /// Overrides the top-level `percent_change_baseline` for just this
+        /// ticker. Ignored if `reference_price` is also set.
+        #[serde(default)]
+        baseline: Option<PercentChangeBaseline>,
+        /// A fixed price to compute this ticker's percent change against
+        /// instead of `prev_close` or `open`, e.g. what you actually paid for
+        /// it. Wins over both this entry's `baseline` and the top-level
+        /// `percent_change_baseline` when set.
+        #[serde(default)]
+        reference_price: Option<f64>,
+        /// This ticker's quote currency as an ISO 4217 code (plus `BTC`),
+        /// e.g. `"EUR"` for a Frankfurt-listed stock alongside NYSE ones
+        /// quoted in dollars. No provider here reports a quote's currency,
+        /// so this has to be set by hand; defaults to `"USD"`. See
+        /// [`crate::currency::symbol`] for how it's rendered.
+        #[serde(default)]
+        currency: Option<String>,
+        /// Divides this ticker's price fields by a fixed amount before
+        /// display, e.g. `100` for a London-listed ticker quoted in pence
+        /// (GBX) so it renders in pounds instead. Pair with `currency:
+        /// "GBP"` so `VWRL.L` shows `£94.12` rather than `$9412.00`.
+        #[serde(default)]
+        unit_divisor: Option<f64>,
+        /// A glyph or emoji shown before this ticker's symbol, e.g. `""`
+        /// for AAPL or `"₿"` for a bitcoin entry, so a watchlist reads at a
+        /// glance without needing `display_name` to spell it out. Rendered
+        /// alongside the symbol rather than in place of it.
+        #[serde(default)]
+        icon: Box<Option<String>>,
+    },
+}
+
+impl TickerEntry {
+    pub fn symbol(&self) -> &str {
+        match self {
+            TickerEntry::Plain(symbol) => symbol,
+            TickerEntry::Detailed { symbol, .. } => symbol,
+        }
+    }
+
+    pub fn provider(&self) -> Option<&str> {
+        match self {
+            TickerEntry::Plain(_) => None,
+            TickerEntry::Detailed { provider, .. } => provider.as_deref(),
+        }
+    }
+
+    pub fn thresholds(&self) -> Thresholds {
+        match self {
+            TickerEntry::Plain(_) => Thresholds::default(),
+            TickerEntry::Detailed { thresholds, .. } => {
+                thresholds.as_deref().copied().unwrap_or_default()
+            }
+        }
+    }
+
+    /// This entry's own `precision`, if it set one -- `None` defers to the
+    /// top-level `Config::precision`, and from there to the built-in default
+    /// of 2 places. See [`Config::precision_for`].
+    pub fn precision(&self) -> Option<usize> {
+        match self {
+            TickerEntry::Plain(_) => None,
+            TickerEntry::Detailed { precision, .. } => *precision,
+        }
+    }
+
+    pub fn display_name(&self) -> Option<&str> {
+        match self {
+            TickerEntry::Plain(_) => None,
+            TickerEntry::Detailed { display_name, .. } => display_name.as_deref(),
+        }
+    }
+
+    pub fn cache_seconds(&self) -> Option<u64> {
+        match self {
+            TickerEntry::Plain(_) => None,
+            TickerEntry::Detailed { cache_seconds, .. } => *cache_seconds,
+        }
+    }
+
+    pub fn market_hours(&self) -> Option<&MarketHours> {
+        match self {
+            TickerEntry::Plain(_) => None,
+            TickerEntry::Detailed { market_hours, .. } => market_hours.as_deref(),
+        }
+    }
+
+    pub fn weekend_days(&self) -> Option<&[Weekday]> {
+        match self {
+            TickerEntry::Plain(_) => None,
+            TickerEntry::Detailed { weekend_days, .. } => weekend_days.as_deref(),
+        }
+    }
+
+    pub fn currency(&self) -> Option<&str> {
+        match self {
+            TickerEntry::Plain(_) => None,
+            TickerEntry::Detailed { currency, .. } => currency.as_deref(),
+        }
+    }
+
+    pub fn unit_divisor(&self) -> Option<f64> {
+        match self {
+            TickerEntry::Plain(_) => None,
+            TickerEntry::Detailed { unit_divisor, .. } => *unit_divisor,
+        }
+    }
+
+    pub fn icon(&self) -> Option<&str> {
+        match self {
+            TickerEntry::Plain(_) => None,
+            TickerEntry::Detailed { icon, .. } => icon.as_deref(),
+        }
+    }
+
+    pub fn baseline(&self) -> Option<PercentChangeBaseline> {
+        match self {
+            TickerEntry::Plain(_) => None,
+            TickerEntry::Detailed { baseline, .. } => *baseline,
+        }
+    }
+
+    pub fn reference_price(&self) -> Option<f64> {
+        match self {
+            TickerEntry::Plain(_) => None,
+            TickerEntry::Detailed {
+                reference_price, ..
+            } => *reference_price,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct Config {
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// A shell command whose stdout is used as the API key, e.g.
+    /// `"pass show tiingo/api"`, so the token itself never has to live in
+    /// the config file. Ignored if `api_key` is also set.
+    #[serde(default)]
+    pub api_key_cmd: Option<String>,
+    /// A Secret Service (GNOME Keyring / KWallet) lookup id, e.g.
+    /// `"stocker/tiingo"`, used as an alternative to file-based keys.
+    /// Ignored if `api_key` or `api_key_cmd` already produced a key.
+    #[serde(default)]
+    pub api_key_secret: Option<String>,
+    /// The default watchlist, used when `--watchlist` isn't given.
+    #[serde(default)]
+    pub tickers: Vec<TickerEntry>,
+    /// Named alternative watchlists, e.g. `[watchlists.tech]`, selected with
+    /// `--watchlist tech` instead of the default `tickers` list.
+    #[serde(default)]
+    pub watchlists: Option<HashMap<String, Vec<TickerEntry>>>,
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub providers: Option<Vec<String>>,
+    /// Set to `"pango"` when the waybar module is configured with
+    /// `"markup": "pango"`, so `text` can carry inline styling.
+    #[serde(default)]
+    pub markup: Option<String>,
+    /// Overrides waybar's `text` field. Placeholders: `{ticker}`, `{last}`,
+    /// `{pct}`, `{volume}`, `{cache_age}`.
+    #[serde(default)]
+    pub text_template: Option<String>,
+    /// Overrides waybar's `tooltip` field. Same placeholders as `text_template`.
+    #[serde(default)]
+    pub tooltip_template: Option<String>,
+    /// Decimal places shown for the price and percent change, for every
+    /// ticker that doesn't set its own `precision`. Defaults to 2 -- too
+    /// coarse for FX pairs (want 4-5) or a sub-cent penny stock, and more
+    /// than needed for an index quoted in whole points.
+    #[serde(default)]
+    pub precision: Option<usize>,
+    /// The URL opened in a browser on a right-click while `stocker daemon`
+    /// is handling waybar click events, e.g.
+    /// `"https://finance.yahoo.com/quote/{ticker}"`. Same placeholders as
+    /// `text_template`. A right-click does nothing if this isn't set.
+    #[serde(default)]
+    pub click_url_template: Option<String>,
+    /// If set, `stocker daemon` also serves `GET /quote/<ticker>` and
+    /// `GET /all` as JSON on `127.0.0.1:<http_port>`, for local tools (eww
+    /// `listen`, custom dashboards) that would rather poll plain HTTP than
+    /// speak D-Bus. Unset by default, so nothing listens unless asked to.
+    #[serde(default)]
+    pub http_port: Option<u16>,
+    /// How often streaming formats like i3bar re-fetch and re-emit. Defaults
+    /// to 30 seconds.
+    #[serde(default)]
+    pub rotation_seconds: Option<u64>,
+    /// How often `stocker daemon` reprints its current quote, independent of
+    /// `rotation_seconds`. Defaults to the rotation interval itself (one
+    /// print per fetch, matching prior behavior); set lower to keep e.g. a
+    /// "last updated Ns ago" tooltip ticking between fetches, per waybar's
+    /// `exec` + `"return-type": "json"` continuous-output contract.
+    #[serde(default)]
+    pub print_interval_seconds: Option<u64>,
+    /// Overrides the cache's weekday TTL. Defaults to 300 seconds. Ignored
+    /// for a day matched by `cache_schedule`.
+    #[serde(default)]
+    pub cache_seconds: Option<u64>,
+    /// Overrides where cached quotes are stored. Defaults to
+    /// `$XDG_CACHE_HOME/stocker/` — set this if that location isn't writable
+    /// or multiple sandboxed instances need separate caches. Takes priority
+    /// over `cache_namespace` and the automatic per-config-file namespacing
+    /// described there.
+    #[serde(default)]
+    pub cache_dir: Option<PathBuf>,
+    /// Names the cache subdirectory used when running with a `--config` file
+    /// other than the default one, so e.g. a "work" and a "personal" config
+    /// with overlapping tickers don't share TTL state. Defaults to a short
+    /// hash of the config file's path, which is enough to keep them apart
+    /// but not to a human skimming `$XDG_CACHE_HOME/stocker/`. Ignored when
+    /// `cache_dir` is set, and never applied to the default config file
+    /// itself, so a plain `stocker` invocation keeps using the same cache
+    /// location it always has.
+    #[serde(default)]
+    pub cache_namespace: Option<String>,
+    /// Per-day, per-time-range cache TTLs, e.g. an aggressive refresh during
+    /// market hours and a slow one overnight. The first matching rule wins;
+    /// if none match (or this isn't set), `cache_seconds` and the
+    /// weekday/weekend split apply instead.
+    #[serde(default)]
+    pub cache_schedule: Option<Vec<ScheduleRule>>,
+    /// The relevant exchange's trading hours. Outside them, the cache is
+    /// held valid until the next open regardless of `cache_seconds`, since
+    /// there's nothing new to fetch until the market reopens. Checked after
+    /// `cache_schedule`, so a schedule rule can still carve out its own
+    /// after-hours TTL if this is too coarse.
+    #[serde(default)]
+    pub market_hours: Option<MarketHours>,
+    /// When true, an outside-trading-hours quote is never re-fetched, no
+    /// matter what `cache_seconds` or `cache_schedule` would otherwise
+    /// dictate -- the cache holds whatever it already has from the last
+    /// close until `market_hours` (a ticker's own, or this top-level
+    /// setting) says the exchange has reopened. Has no effect without
+    /// `market_hours` configured somewhere, since there's no notion of
+    /// "closed" to check against. Defaults to off, since some setups do
+    /// want a schedule rule's overnight polling to run regardless.
+    #[serde(default)]
+    pub fetch_only_when_open: Option<bool>,
+    /// Extra full-day closures, as `"YYYY-MM-DD"` strings, added on top of
+    /// the built-in US market holiday calendar -- e.g. a foreign exchange's
+    /// own holidays, or a date this crate's calendar hasn't been updated
+    /// for yet. On a holiday, `cache_ttl_for` applies the weekend TTL (or,
+    /// with `market_hours` set, holds the cache until the next non-holiday
+    /// open) regardless of what day of the week it falls on.
+    #[serde(default)]
+    pub holidays: Option<Vec<String>>,
+    /// Scheduled half days, as `"YYYY-MM-DD" = "HH:MM"` (UTC close time)
+    /// entries, added on top of the built-in US market early-close table --
+    /// e.g. the day after Thanksgiving, or a one-off closure the table
+    /// hasn't caught up to. With `market_hours` set, `cache_ttl_for` treats
+    /// the market as closed from this time on the given date instead of
+    /// `market_hours.close`, so the widget stops expecting fresh intraday
+    /// data after the early bell.
+    #[serde(default)]
+    pub early_closes: Option<HashMap<String, String>>,
+    /// Which days count as "weekend" for `cache_ttl_for`'s final fallback,
+    /// once `cache_schedule` and `market_hours` have both had no say.
+    /// Defaults to Saturday/Sunday; set this for an exchange with a
+    /// different trading week, e.g. `["fri", "sat"]` for the Tel Aviv Stock
+    /// Exchange (Sunday-Thursday trading). A `TickerEntry::Detailed`'s own
+    /// `weekend_days` overrides this per ticker, the same way its
+    /// `market_hours` does.
+    #[serde(default)]
+    pub weekend_days: Option<Vec<Weekday>>,
+    /// Shows a provider's extended-hours print (currently only Tiingo's IEX
+    /// endpoint reports one) instead of freezing on the regular session's
+    /// closing print once the market's shut. When on, `waybar` shows that
+    /// price and its change from the close, and its `class` gets an
+    /// `"afterhours"` entry alongside the usual up/down one; other formats
+    /// ignore this setting and keep showing the regular session's close.
+    /// Defaults to off, since a print with no separate volume/quote
+    /// depth backing it can be a lot noisier than the regular close.
+    #[serde(default)]
+    pub show_after_hours: Option<bool>,
+    /// Which reference price `percent_change` is computed against, for every
+    /// ticker that doesn't set its own `baseline` or `reference_price`.
+    /// Defaults to `prev_close`. A ticker whose provider doesn't report an
+    /// `open` (see `Quote::open`) falls back to `prev_close` regardless of
+    /// this setting -- see [`Config::baseline_price`].
+    #[serde(default)]
+    pub percent_change_baseline: Option<PercentChangeBaseline>,
+    /// Renders a `▂▃▅▇▆`-style sparkline of the last 24 hours of cached
+    /// prices (see `cache::history_since`) alongside the quote. Only
+    /// `waybar` currently draws one. Defaults to off, since a ticker
+    /// fetched for the first time -- or one whose cache was just cleared --
+    /// has no history to draw from yet and simply omits the sparkline.
+    #[serde(default)]
+    pub show_sparkline: Option<bool>,
+    /// Converts every ticker's price into this ISO 4217 currency (e.g.
+    /// `"EUR"`) before display, using a live exchange rate fetched (and
+    /// cached, same as any other quote) as a synthetic `fx:<from><to>`
+    /// ticker -- so it needs a `tiingo-fx`-capable `api_key` configured the
+    /// same as a regular `fx:` entry would. A ticker whose own `currency`
+    /// already matches is left alone; if the rate can't be fetched, that
+    /// ticker is shown unconverted in its own currency rather than failing
+    /// outright. Unset by default, since most watchlists are single-currency.
+    #[serde(default)]
+    pub base_currency: Option<String>,
+    /// Display names for plain ticker symbols, e.g. `"^GSPC" = "S&P500"`,
+    /// for when a symbol's provider-facing form isn't what should show on
+    /// the bar. A `TickerEntry::Detailed`'s own `display_name` wins over this.
+    #[serde(default)]
+    pub aliases: Option<HashMap<String, String>>,
+    /// Shows the absolute change (`last` minus the percent-change baseline,
+    /// e.g. `+1.23`) alongside the percentage in the default (non-template)
+    /// text, since the percentage alone hides the magnitude for a
+    /// high-priced share. A `text_template`/`tooltip_template` can already
+    /// reference this via `{change}` regardless of this setting. Defaults
+    /// to off, to keep the existing default text unchanged.
+    #[serde(default)]
+    pub show_absolute_change: Option<bool>,
+    /// A glyph to prepend to the text for each threshold class (`"critdown"`,
+    /// `"down"`, `"flat"`, `"up"`, `"wayup"`, `"paused"`, `"stale"` -- see
+    /// `output::class`), e.g. `{ up = "▲", down = "▼" }` or a nerd-font icon,
+    /// so a class still reads at a glance without relying on waybar CSS
+    /// coloring. A class with no entry here just gets no glyph. See
+    /// `output::Options::glyph` for how it's resolved.
+    #[serde(default)]
+    pub glyphs: Option<HashMap<String, String>>,
+    /// The magnitude (e.g. volume) must reach before it's shown compacted as
+    /// `1.2M` / `3.4B` instead of spelled out in full -- see
+    /// `crate::numfmt::compact`. Defaults to 1000, so anything below four
+    /// digits is left as-is.
+    #[serde(default)]
+    pub compact_number_threshold: Option<f64>,
+    /// Fetches each ticker's 52-week high/low (via a synthetic `52w:<symbol>`
+    /// lookup against Tiingo's daily-prices endpoint, cached and refreshed
+    /// like any other ticker) and shows where the current price sits within
+    /// that range. Only `waybar`'s tooltip currently draws it. Defaults to
+    /// off, since it costs an extra request per ticker on cache miss.
+    #[serde(default)]
+    pub show_52_week_range: Option<bool>,
+    /// Shows the bid/ask spread (Tiingo IEX's `bidPrice`/`askPrice`) alongside
+    /// the price in the default (non-template) text, useful for judging
+    /// liquidity on thinly traded tickers. Tooltip always shows it when the
+    /// provider reports one, regardless of this setting -- this only affects
+    /// the more space-constrained text. Defaults to off, and providers other
+    /// than Tiingo's IEX endpoint never report one anyway.
+    #[serde(default)]
+    pub show_spread: Option<bool>,
+    /// Shows every active ticker's current percent change as an extra
+    /// tooltip section, sourced from whatever's already cached (no extra
+    /// fetches), so hovering gives the full watchlist without waiting for
+    /// the daemon's rotation to cycle to it. Only `waybar`'s tooltip
+    /// currently draws it. Defaults to off, since a long watchlist makes for
+    /// a long tooltip.
+    #[serde(default)]
+    pub show_watchlist_tooltip: Option<bool>,
+    /// Overrides the decimal/thousands separators used for prices and
+    /// changes, e.g. `"de_DE"` for `1.234,56` instead of `1,234.56` -- see
+    /// `crate::locale`. Accepts a bare language code or a full POSIX locale
+    /// string. Falls back to the `LC_NUMERIC`/`LC_ALL` environment variables
+    /// if unset, and to US-style formatting if neither is recognized.
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Colors waybar's output on a continuous red->grey->green scale
+    /// proportional to the percent change (see `output::gradient_color`)
+    /// instead of the usual four discrete threshold colors, so a small dip
+    /// and a crash are visually distinct rather than both just "down".
+    /// Defaults to off, keeping the existing discrete colors.
+    #[serde(default)]
+    pub show_color_gradient: Option<bool>,
+    /// Renames one or more of the emitted classes (`"critdown"`, `"down"`,
+    /// `"flat"`, `"up"`, `"wayup"`, `"paused"`, `"stale"` -- see
+    /// `output::class`) in waybar's `class`/`alt` fields and the CSV `class`
+    /// column, e.g. `{ wayup = "surge" }`. A class with no entry here is
+    /// emitted under its usual name. Threshold classification, `[glyphs]`,
+    /// and the color gradient are unaffected -- they still key off the
+    /// canonical names. See `output::display_class`.
+    #[serde(default)]
+    pub class_names: Option<HashMap<String, String>>,
+    /// Pads waybar's default text (not templates) with trailing spaces to at
+    /// least this many characters, so the module keeps a constant footprint
+    /// as it rotates between tickers with shorter and longer prices instead
+    /// of jittering the rest of the bar. Text longer than this is left
+    /// alone. Defaults to no padding.
+    #[serde(default)]
+    pub text_width: Option<usize>,
+    /// Fetches each ticker's market cap (via a synthetic `mcap:<symbol>`
+    /// lookup against Tiingo's fundamentals endpoint, cached and refreshed
+    /// once a day like `show_52_week_range`) and shows it in the tooltip.
+    /// Only `waybar`'s tooltip currently draws it. Defaults to off, since it
+    /// costs an extra request per ticker on cache miss and Tiingo
+    /// fundamentals is a paid add-on.
+    #[serde(default)]
+    pub show_market_cap: Option<bool>,
+    /// Other config files to merge in, resolved relative to this file's
+    /// directory, e.g. `["secrets.toml"]` to keep an API key out of a
+    /// publicly versioned dotfiles repo. Fields already set by this file
+    /// take priority over the same field in an included file.
+    #[serde(default)]
+    pub include: Option<Vec<String>>,
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/stocker/config.toml`, falling back to
+    /// `~/.config/stocker/config.toml` when `XDG_CONFIG_HOME` isn't set.
+    pub fn default_path() -> std::path::PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(|| std::path::PathBuf::from("."))
+            .join("stocker")
+            .join("config.toml")
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut config = Self::load_file(path)?;
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        for include in std::mem::take(&mut config.include).into_iter().flatten() {
+            let included = Self::load_file(&dir.join(&include))?;
+            config.merge_from(included);
+        }
+        config.apply_env_overrides();
+        config.resolve_api_key_cmd()?;
+        config.resolve_api_key_secret()?;
+        config.resolve_cache_dir(path);
+        Ok(config)
+    }
+
+    /// Namespaces `cache_dir` by `cache_namespace` (or a hash of `path`) when
+    /// `path` isn't the default config location and `cache_dir` wasn't
+    /// already set explicitly -- see the doc comment on `cache_namespace`.
+    fn resolve_cache_dir(&mut self, path: &Path) {
+        if self.cache_dir.is_some() || path == Self::default_path() {
+            return;
+        }
+        let namespace = self
+            .cache_namespace
+            .clone()
+            .unwrap_or_else(|| config_path_hash(path));
+        let root = dirs::cache_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("stocker");
+        self.cache_dir = Some(root.join(namespace));
+    }
+
+    /// Parses `path` as TOML, YAML, or JSON depending on its extension,
+    /// defaulting to TOML for an unrecognized or missing one.
+    fn load_file(path: &Path) -> Result<Self> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("reading config file {}", path.display()))?;
+        let raw = interpolate_env(&raw);
+        let context = || format!("parsing config file {}", path.display());
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&raw).with_context(context),
+            Some("json") => serde_json::from_str(&raw).with_context(context),
+            _ => toml::from_str(&raw).with_context(context),
+        }
+    }
+
+    /// Fills in any field this config didn't set from `other`, so an
+    /// `include`d file only supplies what the including file leaves out.
+    fn merge_from(&mut self, other: Config) {
+        self.api_key = self.api_key.take().or(other.api_key);
+        self.api_key_cmd = self.api_key_cmd.take().or(other.api_key_cmd);
+        self.api_key_secret = self.api_key_secret.take().or(other.api_key_secret);
+        if self.tickers.is_empty() {
+            self.tickers = other.tickers;
+        }
+        match (&mut self.watchlists, other.watchlists) {
+            (Some(watchlists), Some(other)) => {
+                for (name, tickers) in other {
+                    watchlists.entry(name).or_insert(tickers);
+                }
+            }
+            (watchlists @ None, Some(other)) => *watchlists = Some(other),
+            _ => {}
+        }
+        self.provider = self.provider.take().or(other.provider);
+        self.providers = self.providers.take().or(other.providers);
+        self.markup = self.markup.take().or(other.markup);
+        self.text_template = self.text_template.take().or(other.text_template);
+        self.tooltip_template = self.tooltip_template.take().or(other.tooltip_template);
+        self.precision = self.precision.take().or(other.precision);
+        self.click_url_template = self.click_url_template.take().or(other.click_url_template);
+        self.http_port = self.http_port.take().or(other.http_port);
+        self.rotation_seconds = self.rotation_seconds.take().or(other.rotation_seconds);
+        self.print_interval_seconds = self
+            .print_interval_seconds
+            .take()
+            .or(other.print_interval_seconds);
+        self.cache_seconds = self.cache_seconds.take().or(other.cache_seconds);
+        self.cache_dir = self.cache_dir.take().or(other.cache_dir);
+        self.cache_namespace = self.cache_namespace.take().or(other.cache_namespace);
+        self.cache_schedule = self.cache_schedule.take().or(other.cache_schedule);
+        self.market_hours = self.market_hours.take().or(other.market_hours);
+        self.fetch_only_when_open = self
+            .fetch_only_when_open
+            .take()
+            .or(other.fetch_only_when_open);
+        self.holidays = self.holidays.take().or(other.holidays);
+        match (&mut self.early_closes, other.early_closes) {
+            (Some(early_closes), Some(other)) => {
+                for (date, close) in other {
+                    early_closes.entry(date).or_insert(close);
+                }
+            }
+            (early_closes @ None, Some(other)) => *early_closes = Some(other),
+            _ => {}
+        }
+        self.weekend_days = self.weekend_days.take().or(other.weekend_days);
+        self.show_after_hours = self.show_after_hours.take().or(other.show_after_hours);
+        self.percent_change_baseline = self
+            .percent_change_baseline
+            .take()
+            .or(other.percent_change_baseline);
+        self.show_sparkline = self.show_sparkline.take().or(other.show_sparkline);
+        self.base_currency = self.base_currency.take().or(other.base_currency);
+        self.show_absolute_change = self
+            .show_absolute_change
+            .take()
+            .or(other.show_absolute_change);
+        self.compact_number_threshold = self
+            .compact_number_threshold
+            .take()
+            .or(other.compact_number_threshold);
+        self.show_52_week_range = self.show_52_week_range.take().or(other.show_52_week_range);
+        self.show_spread = self.show_spread.take().or(other.show_spread);
+        self.show_watchlist_tooltip = self
+            .show_watchlist_tooltip
+            .take()
+            .or(other.show_watchlist_tooltip);
+        self.locale = self.locale.take().or(other.locale);
+        self.show_color_gradient = self
+            .show_color_gradient
+            .take()
+            .or(other.show_color_gradient);
+        self.class_names = self.class_names.take().or(other.class_names);
+        self.text_width = self.text_width.take().or(other.text_width);
+        self.show_market_cap = self.show_market_cap.take().or(other.show_market_cap);
+        match (&mut self.aliases, other.aliases) {
+            (Some(aliases), Some(other)) => {
+                for (symbol, name) in other {
+                    aliases.entry(symbol).or_insert(name);
+                }
+            }
+            (aliases @ None, Some(other)) => *aliases = Some(other),
+            _ => {}
+        }
+        match (&mut self.glyphs, other.glyphs) {
+            (Some(glyphs), Some(other)) => {
+                for (class, glyph) in other {
+                    glyphs.entry(class).or_insert(glyph);
+                }
+            }
+            (glyphs @ None, Some(other)) => *glyphs = Some(other),
+            _ => {}
+        }
+    }
+
+    /// The display name for a ticker symbol from the `[aliases]` table, if
+    /// one is set. A `TickerEntry`'s own `display_name` takes priority over
+    /// this, since it's more specific.
+    pub fn alias_for(&self, symbol: &str) -> Option<&str> {
+        self.aliases.as_ref()?.get(symbol).map(String::as_str)
+    }
+
+    /// Looks up `api_key_secret` in the Secret Service, if `api_key` wasn't
+    /// already set by the config file, an environment override, or `api_key_cmd`.
+    fn resolve_api_key_secret(&mut self) -> Result<()> {
+        if self.api_key.is_some() {
+            return Ok(());
+        }
+        let Some(id) = &self.api_key_secret else {
+            return Ok(());
+        };
+        self.api_key = Some(crate::secret::lookup(id)?);
+        Ok(())
+    }
+
+    /// Runs `api_key_cmd` and uses its stdout as the API key, if `api_key`
+    /// wasn't already set by the config file or an environment override.
+    fn resolve_api_key_cmd(&mut self) -> Result<()> {
+        if self.api_key.is_some() {
+            return Ok(());
+        }
+        let Some(cmd) = &self.api_key_cmd else {
+            return Ok(());
+        };
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(cmd)
+            .output()
+            .with_context(|| format!("running api_key_cmd: {cmd}"))?;
+        anyhow::ensure!(
+            output.status.success(),
+            "api_key_cmd exited with {}",
+            output.status
+        );
+        let key = String::from_utf8(output.stdout)
+            .with_context(|| format!("api_key_cmd output wasn't valid UTF-8: {cmd}"))?;
+        self.api_key = Some(key.trim().to_string());
+        Ok(())
+    }
+
+    /// Lets `STOCKER_API_KEY`, `STOCKER_TICKERS` (comma-separated), and
+    /// `STOCKER_ROTATION_SECONDS` override the equivalent TOML values, so an
+    /// API key doesn't have to live in a dotfiles repo.
+    fn apply_env_overrides(&mut self) {
+        if let Ok(api_key) = env::var("STOCKER_API_KEY") {
+            self.api_key = Some(api_key);
+        }
+        if let Ok(tickers) = env::var("STOCKER_TICKERS") {
+            self.tickers = tickers
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| TickerEntry::Plain(s.to_string()))
+                .collect();
+        }
+        if let Ok(rotation) = env::var("STOCKER_ROTATION_SECONDS") {
+            if let Ok(rotation) = rotation.parse() {
+                self.rotation_seconds = Some(rotation);
+            }
+        }
+    }
+
+    /// How often streaming formats should re-fetch and re-emit.
+    pub fn rotation_interval(&self) -> Duration {
+        Duration::from_secs(self.rotation_seconds.unwrap_or(30))
+    }
+
+    /// How often `stocker daemon` reprints its current quote. Capped at
+    /// `rotation_interval` so a misconfigured value can't delay rotation.
+    pub fn print_interval(&self) -> Duration {
+        match self.print_interval_seconds {
+            Some(secs) => Duration::from_secs(secs).min(self.rotation_interval()),
+            None => self.rotation_interval(),
+        }
+    }
+
+    /// The cache TTL to use right now for a ticker's quote. A daily-only
+    /// ticker (funds) always gets [`DEFAULT_DAILY_TTL`] regardless of
+    /// `cache_schedule`, `market_hours`, or `ticker_override`, since it has
+    /// nothing to do with market hours. Next, if `fetch_only_when_open` is
+    /// set and `ticker_market_hours` (or the top-level `market_hours`) says
+    /// the exchange is closed, the cache is held until the next open no
+    /// matter what `ticker_override` or `cache_schedule` would otherwise
+    /// say -- that's the whole point of the flag: no network request gets
+    /// made outside trading hours. Otherwise a per-ticker `cache_seconds`
+    /// override wins next, then the first matching `cache_schedule` rule,
+    /// then `ticker_market_hours` (a ticker's own exchange hours) or,
+    /// absent that, the top-level `market_hours` (holding the cache until
+    /// the next open if the market's closed); absent all of those, falls
+    /// back to the top-level `cache_seconds` on a listed weekday or
+    /// [`DEFAULT_WEEKEND_TTL`] on a day in `ticker_weekend_days` (that
+    /// ticker's own override, or `weekend_days`, or plain Saturday/Sunday if
+    /// neither is set -- see [`Config::weekend_days`]).
+    pub fn cache_ttl_for(
+        &self,
+        daily_only: bool,
+        ticker_override: Option<u64>,
+        ticker_market_hours: Option<&MarketHours>,
+        ticker_weekend_days: Option<&[Weekday]>,
+        now: SystemTime,
+    ) -> Duration {
+        if daily_only {
+            return DEFAULT_DAILY_TTL;
+        }
+        if self.fetch_only_when_open.unwrap_or(false) {
+            if let Some(market_hours) = ticker_market_hours.or(self.market_hours.as_ref()) {
+                if !market_hours.is_open(now, self.is_holiday(now), self.early_close_for(now)) {
+                    return market_hours.duration_until_open(now, &|t| self.is_holiday(t));
+                }
+            }
+        }
+        if let Some(seconds) = ticker_override {
+            return Duration::from_secs(seconds);
+        }
+        if let Some(rule) = self
+            .cache_schedule
+            .iter()
+            .flatten()
+            .find(|rule| rule.matches(now))
+        {
+            return Duration::from_secs(rule.ttl_seconds);
+        }
+        let holiday = self.is_holiday(now);
+        if let Some(market_hours) = ticker_market_hours.or(self.market_hours.as_ref()) {
+            if !market_hours.is_open(now, holiday, self.early_close_for(now)) {
+                return market_hours.duration_until_open(now, &|t| self.is_holiday(t));
+            }
+        }
+        let weekend_days = ticker_weekend_days
+            .or(self.weekend_days.as_deref())
+            .unwrap_or(&DEFAULT_WEEKEND_DAYS);
+        if is_weekend(now, weekend_days) || holiday {
+            DEFAULT_WEEKEND_TTL
+        } else {
+            Duration::from_secs(self.cache_seconds.unwrap_or(300))
+        }
+    }
+
+    /// The ticker's exchange session and how long until it next changes, if
+    /// `market_hours` is configured for it (that ticker's own override, or
+    /// the top-level setting). `None` if neither is set, since there's
+    /// nothing to derive a session from.
+    pub fn market_status_for(
+        &self,
+        ticker_market_hours: Option<&MarketHours>,
+        now: SystemTime,
+    ) -> Option<(MarketStatus, Duration)> {
+        let market_hours = ticker_market_hours.or(self.market_hours.as_ref())?;
+        Some(market_hours.status(now, &|t| self.is_holiday(t), &|t| self.early_close_for(t)))
+    }
+
+    /// The price `quote`'s percent change should be computed against.
+    /// `ticker_reference_price` (a `TickerEntry::Detailed`'s own fixed price)
+    /// wins outright; otherwise `ticker_baseline` (that ticker's own
+    /// `baseline`) or, absent that, this config's `percent_change_baseline`
+    /// picks between `quote.prev_close` and `quote.open` -- falling back to
+    /// `prev_close` if `Open` was chosen but this provider didn't report one.
+    pub fn baseline_price(
+        &self,
+        quote: &Quote,
+        ticker_baseline: Option<PercentChangeBaseline>,
+        ticker_reference_price: Option<f64>,
+    ) -> f64 {
+        if let Some(reference_price) = ticker_reference_price {
+            return reference_price;
+        }
+        match ticker_baseline.or(self.percent_change_baseline) {
+            Some(PercentChangeBaseline::Open) => quote.open.unwrap_or(quote.prev_close),
+            Some(PercentChangeBaseline::PrevClose) | None => quote.prev_close,
+        }
+    }
+
+    /// Decimal places shown for both the price and the percent change:
+    /// `ticker_precision` (a `TickerEntry::Detailed`'s own `precision`) wins,
+    /// then this config's top-level `precision`, then the built-in default
+    /// of 2 -- fine for most US equities, but too coarse for FX pairs
+    /// (4-5 places) or sub-cent penny stocks.
+    pub fn precision_for(&self, ticker_precision: Option<usize>) -> usize {
+        ticker_precision.or(self.precision).unwrap_or(2)
+    }
+
+    /// The magnitude threshold above which a number (currently just volume)
+    /// is shown compacted -- see `crate::numfmt::compact`. Defaults to 1000.
+    pub fn compact_threshold(&self) -> f64 {
+        self.compact_number_threshold.unwrap_or(1_000.0)
+    }
+
+    /// Whether `now`'s date is a full-day market closure -- the built-in US
+    /// market calendar, or an entry in this config's `holidays` list.
+    fn is_holiday(&self, now: SystemTime) -> bool {
+        let date = date_string(now);
+        US_MARKET_HOLIDAYS.contains(&date.as_str())
+            || self.holidays.iter().flatten().any(|d| *d == date)
+    }
+
+    /// `now`'s early-close time (`"HH:MM"`, UTC, parsed to minute-of-day) if
+    /// this date is a scheduled half day -- a config's own `early_closes`
+    /// entry for the date, else the built-in US market table. `None` on an
+    /// ordinary full trading day.
+    fn early_close_for(&self, now: SystemTime) -> Option<u32> {
+        let date = date_string(now);
+        self.early_closes
+            .as_ref()
+            .and_then(|closes| closes.get(&date))
+            .map(String::as_str)
+            .or_else(|| {
+                US_MARKET_EARLY_CLOSES
+                    .iter()
+                    .find(|(d, _)| *d == date)
+                    .map(|(_, close)| *close)
+            })
+            .and_then(parse_hhmm)
+    }
+
+    /// The provider to use when a ticker doesn't request its own. Falls back
+    /// to the keyless Stooq backend if no `api_key` was configured, so
+    /// `stocker` works out of the box with zero setup.
+    fn default_provider(&self) -> &str {
+        match &self.provider {
+            Some(name) => name,
+            None if self.api_key.is_some() => "tiingo",
+            None => "stooq",
+        }
+    }
+
+    /// The ordered list of providers to try for a ticker that hasn't
+    /// requested its own backend. If `providers` isn't set this is just the
+    /// single [`Config::default_provider`].
+    pub fn provider_chain(&self) -> Vec<&str> {
+        match &self.providers {
+            Some(chain) if !chain.is_empty() => chain.iter().map(String::as_str).collect(),
+            _ => vec![self.default_provider()],
+        }
+    }
+
+    /// A JSON Schema describing this config format, for editor
+    /// autocompletion and validation of the TOML/YAML file.
+    pub fn json_schema() -> schemars::Schema {
+        schemars::schema_for!(Config)
+    }
+
+    /// The watchlist to use: `tickers` by default, or the named list under
+    /// `watchlists` if `--watchlist` selected one.
+    pub fn active_tickers(&self, watchlist: Option<&str>) -> Result<&[TickerEntry]> {
+        let Some(name) = watchlist else {
+            return Ok(&self.tickers);
+        };
+        self.watchlists
+            .as_ref()
+            .and_then(|watchlists| watchlists.get(name))
+            .map(Vec::as_slice)
+            .with_context(|| format!("no watchlist named \"{name}\" in the config"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        date_string, interpolate_env, is_weekend, parse_compact_market_hours, weekday_index,
+        MarketHours, MarketStatus, ScheduleRule, Weekday, DEFAULT_WEEKEND_DAYS,
+        US_MARKET_EARLY_CLOSES, US_MARKET_HOLIDAYS,
+    };
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn interpolate_env_leaves_plain_text_unchanged() {
+        assert_eq!(interpolate_env("api_key = \"abc\""), "api_key = \"abc\"");
+    }
+
+    #[test]
+    fn interpolate_env_leaves_unset_variable_literal() {
+        let raw = "api_key = \"${STOCKER_TEST_DEFINITELY_UNSET_VAR}\"";
+        assert_eq!(interpolate_env(raw), raw);
+    }
+
+    #[test]
+    fn interpolate_env_leaves_unterminated_placeholder_literal() {
+        let raw = "api_key = \"${OOPS\"";
+        assert_eq!(interpolate_env(raw), raw);
+    }
+
+    // 1970-01-01 was a Thursday; day 4 (1970-01-05) is the following Monday
+    // and day 2 (1970-01-03) is the preceding Saturday.
+    fn at(days: u64, minute_of_day: u32) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(days * 86_400 + minute_of_day as u64 * 60)
+    }
+
+    #[test]
+    fn weekday_index_matches_known_dates() {
+        assert_eq!(weekday_index(SystemTime::UNIX_EPOCH), 3); // Thursday
+        assert_eq!(weekday_index(at(4, 0)), 0); // Monday
+        assert_eq!(weekday_index(at(2, 0)), 5); // Saturday
+    }
+
+    #[test]
+    fn parse_compact_market_hours_defaults_to_a_monday_friday_week() {
+        let hours = parse_compact_market_hours("08:00-16:30").unwrap();
+        assert_eq!(hours.open, "08:00");
+        assert_eq!(hours.close, "16:30");
+        assert_eq!(
+            hours.days,
+            [
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_compact_market_hours_ignores_a_trailing_timezone_label() {
+        let hours = parse_compact_market_hours("08:00-16:30 Europe/London").unwrap();
+        assert_eq!(hours.open, "08:00");
+        assert_eq!(hours.close, "16:30");
+    }
+
+    #[test]
+    fn parse_compact_market_hours_rejects_a_missing_dash() {
+        assert!(parse_compact_market_hours("08:00 16:30").is_err());
+    }
+
+    #[test]
+    fn is_weekend_true_only_on_saturday_and_sunday() {
+        assert!(is_weekend(at(2, 0), &DEFAULT_WEEKEND_DAYS)); // Saturday
+        assert!(is_weekend(at(3, 0), &DEFAULT_WEEKEND_DAYS)); // Sunday
+        assert!(!is_weekend(at(4, 0), &DEFAULT_WEEKEND_DAYS)); // Monday
+    }
+
+    #[test]
+    fn is_weekend_honors_a_custom_weekend() {
+        // Tel Aviv Stock Exchange: Sunday-Thursday trading, Friday/Saturday off.
+        let tase_weekend = [Weekday::Fri, Weekday::Sat];
+        assert!(is_weekend(at(2, 0), &tase_weekend)); // Saturday
+        assert!(!is_weekend(at(3, 0), &tase_weekend)); // Sunday
+        assert!(!is_weekend(at(4, 0), &tase_weekend)); // Monday
+        assert!(is_weekend(at(1, 0), &tase_weekend)); // Friday
+    }
+
+    #[test]
+    fn schedule_rule_with_no_time_range_matches_all_day_on_listed_days() {
+        let rule = ScheduleRule {
+            days: vec![Weekday::Mon],
+            start: None,
+            end: None,
+            ttl_seconds: 60,
+        };
+        assert!(rule.matches(at(4, 0))); // Monday, midnight
+        assert!(rule.matches(at(4, 23 * 60))); // Monday, 23:00
+        assert!(!rule.matches(at(5, 0))); // Tuesday
+    }
+
+    #[test]
+    fn schedule_rule_with_time_range_only_matches_inside_the_window() {
+        let rule = ScheduleRule {
+            days: vec![Weekday::Mon],
+            start: Some("09:30".to_string()),
+            end: Some("16:00".to_string()),
+            ttl_seconds: 60,
+        };
+        assert!(rule.matches(at(4, 9 * 60 + 30))); // 09:30, inclusive start
+        assert!(rule.matches(at(4, 12 * 60)));
+        assert!(!rule.matches(at(4, 16 * 60))); // 16:00, exclusive end
+        assert!(!rule.matches(at(4, 8 * 60)));
+        assert!(!rule.matches(at(5, 12 * 60))); // right time, wrong day
+    }
+
+    #[test]
+    fn date_string_matches_known_dates() {
+        assert_eq!(date_string(SystemTime::UNIX_EPOCH), "1970-01-01");
+        assert_eq!(date_string(at(19_723, 0)), "2024-01-01");
+        assert_eq!(date_string(at(19_908, 0)), "2024-07-04");
+        assert!(US_MARKET_HOLIDAYS.contains(&date_string(at(19_723, 0)).as_str()));
+        assert!(!US_MARKET_HOLIDAYS.contains(&date_string(at(19_724, 0)).as_str()));
+    }
+
+    #[test]
+    fn early_closes_table_has_the_day_after_thanksgiving_2024() {
+        let day_after_thanksgiving = date_string(at(20_056, 0));
+        assert_eq!(day_after_thanksgiving, "2024-11-29");
+        assert!(US_MARKET_EARLY_CLOSES
+            .iter()
+            .any(|(date, _)| *date == day_after_thanksgiving));
+    }
+
+    fn nyse_hours() -> MarketHours {
+        MarketHours {
+            days: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            open: "13:30".to_string(),
+            close: "20:00".to_string(),
+            pre_market_open: None,
+            after_hours_close: None,
+        }
+    }
+
+    #[test]
+    fn market_hours_is_open_only_during_the_window_on_listed_days() {
+        let hours = nyse_hours();
+        assert!(hours.is_open(at(4, 13 * 60 + 30), false, None)); // Monday, open
+        assert!(!hours.is_open(at(4, 20 * 60), false, None)); // Monday, right at close
+        assert!(!hours.is_open(at(4, 12 * 60), false, None)); // Monday, before open
+        assert!(!hours.is_open(at(2, 14 * 60), false, None)); // Saturday, market-hours time
+        assert!(!hours.is_open(at(4, 13 * 60 + 30), true, None)); // Monday, but a holiday
+    }
+
+    #[test]
+    fn market_hours_is_open_honors_an_early_close() {
+        let hours = nyse_hours();
+        let early_close = 17 * 60; // 1pm ET
+        assert!(hours.is_open(at(4, 16 * 60 + 30), false, Some(early_close))); // still before the early close
+        assert!(!hours.is_open(at(4, 17 * 60 + 30), false, Some(early_close))); // past the early close, before the normal one
+    }
+
+    #[test]
+    fn market_hours_status_reports_pre_market_and_after_hours() {
+        let mut hours = nyse_hours();
+        hours.pre_market_open = Some("09:00".to_string());
+        hours.after_hours_close = Some("22:00".to_string());
+        let (status, change) = hours.status(at(4, 9 * 60 + 30), &|_| false, &|_| None); // Monday 09:30
+        assert_eq!(status, MarketStatus::PreMarket);
+        assert!(status.heading_to_open());
+        assert_eq!(
+            change,
+            Duration::from_secs((13 * 60 + 30 - 9 * 60 - 30) * 60)
+        );
+
+        let (status, _) = hours.status(at(4, 21 * 60), &|_| false, &|_| None); // Monday 21:00
+        assert_eq!(status, MarketStatus::AfterHours);
+        assert!(!status.heading_to_open());
+
+        let (status, _) = hours.status(at(4, 13 * 60 + 30), &|_| false, &|_| None); // Monday, open
+        assert_eq!(status, MarketStatus::Open);
+    }
+
+    #[test]
+    fn market_hours_status_falls_back_to_closed_without_pre_or_after_windows() {
+        let hours = nyse_hours();
+        let (status, _) = hours.status(at(4, 21 * 60), &|_| false, &|_| None); // Monday, well after close
+        assert_eq!(status, MarketStatus::Closed);
+    }
+
+    #[test]
+    fn market_hours_status_honors_an_early_close() {
+        let hours = nyse_hours();
+        let one_pm = 13 * 60;
+        let (status, _) = hours.status(at(4, 14 * 60), &|_| false, &|_| Some(one_pm)); // Monday 14:00, half day
+        assert_eq!(status, MarketStatus::Closed);
+    }
+
+    #[test]
+    fn market_hours_duration_until_open_skips_to_next_listed_day() {
+        let hours = nyse_hours();
+        // Friday evening, well after close -- next open is Monday 13:30.
+        let friday_evening = at(8, 21 * 60);
+        let until_open = hours.duration_until_open(friday_evening, &|_| false);
+        assert_eq!(until_open, Duration::from_secs(232_200));
+    }
+
+    #[test]
+    fn market_hours_duration_until_open_skips_a_holiday_on_a_listed_day() {
+        let hours = nyse_hours();
+        // Friday evening; treat Monday as a holiday, so the next open is Tuesday 13:30.
+        let friday_evening = at(8, 21 * 60);
+        let monday = at(11, 0);
+        let until_open = hours.duration_until_open(friday_evening, &|t| t == monday);
+        assert_eq!(until_open, Duration::from_secs(232_200 + 86_400));
+    }
+
+    #[test]
+    fn market_hours_duration_until_open_same_day_before_open() {
+        let hours = nyse_hours();
+        let monday_early = at(4, 6 * 60);
+        let until_open = hours.duration_until_open(monday_early, &|_| false);
+        assert_eq!(
+            until_open,
+            Duration::from_secs((13 * 60 + 30 - 6 * 60) * 60)
+        );
+    }
+}
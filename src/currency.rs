@@ -0,0 +1,36 @@
+//! Maps an ISO 4217 currency code (plus `BTC`, which isn't one but shows up
+//! in crypto watchlists all the same) to the symbol and placement a reader
+//! would expect, e.g. `$100.00` but `100,00 €`.
+
+/// The symbol for `code` and whether it goes after the amount, e.g.
+/// `("$", false)` for USD or `("€", true)` for EUR. Falls back to `(code,
+/// false)` -- i.e. printing the bare code as a prefix -- for anything not in
+/// this (deliberately short) table, since a made-up symbol would be worse
+/// than the ISO code itself.
+pub fn symbol(code: &str) -> (&str, bool) {
+    match code.to_ascii_uppercase().as_str() {
+        "USD" => ("$", false),
+        "EUR" => ("€", true),
+        "GBP" => ("£", false),
+        "JPY" => ("¥", false),
+        "CHF" => ("CHF", true),
+        "BTC" => ("₿", false),
+        _ => (code, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_currencies_use_their_symbol() {
+        assert_eq!(symbol("USD"), ("$", false));
+        assert_eq!(symbol("eur"), ("€", true));
+    }
+
+    #[test]
+    fn unknown_currencies_fall_back_to_their_code_as_a_prefix() {
+        assert_eq!(symbol("XYZ"), ("XYZ", false));
+    }
+}
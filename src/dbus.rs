@@ -0,0 +1,105 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use zbus::blocking::{connection, Connection};
+use zbus::object_server::SignalEmitter;
+use zbus::{block_on, interface};
+
+use crate::config::Config;
+use crate::ipc::{self, DaemonState};
+use crate::quote::Quote;
+
+/// The `org.stocker.Quotes` session-bus interface: lets other desktop
+/// widgets (eww, custom scripts) read the daemon's already-fetched quotes
+/// instead of hitting the provider API themselves.
+struct Quotes {
+    state: Arc<Mutex<DaemonState>>,
+    config: Arc<Config>,
+}
+
+#[interface(name = "org.stocker.Quotes")]
+impl Quotes {
+    /// Returns the last known `(last, prev_close)` pair for `ticker`.
+    async fn get_quote(&self, ticker: String) -> zbus::fdo::Result<(f64, f64)> {
+        match ipc::lookup_quote(&self.state, &self.config, &ticker) {
+            Some(quote) => Ok((quote.last, quote.prev_close)),
+            None => Err(zbus::fdo::Error::Failed(format!(
+                "no cached quote for {ticker}"
+            ))),
+        }
+    }
+
+    /// Emitted whenever the daemon's rotation loop fetches a new quote, so
+    /// subscribers can update without polling `GetQuote`.
+    #[zbus(signal)]
+    async fn quote_updated(
+        emitter: &SignalEmitter<'_>,
+        ticker: &str,
+        last: f64,
+        prev_close: f64,
+    ) -> zbus::Result<()>;
+}
+
+/// Connects to the session bus, claims `org.stocker.Quotes`, and registers
+/// the interface there. Returns the connection so the daemon's rotation loop
+/// can later emit `QuoteUpdated` through it.
+pub fn serve(state: Arc<Mutex<DaemonState>>, config: Arc<Config>) -> Result<Connection> {
+    connection::Builder::session()
+        .context("connecting to the session bus")?
+        .name("org.stocker.Quotes")
+        .context("requesting the org.stocker.Quotes bus name")?
+        .serve_at("/org/stocker/Quotes", Quotes { state, config })
+        .context("registering the org.stocker.Quotes interface")?
+        .build()
+        .context("building the session bus connection")
+}
+
+/// Asks a running `stocker daemon`'s `org.stocker.Quotes` service for
+/// `ticker`'s quote, so a second bar instance (e.g. a dual-head waybar
+/// setup) can reuse it instead of fetching and caching independently.
+/// Returns `None` if no daemon is running or it hasn't cached this ticker
+/// yet — callers should fall back to fetching it themselves.
+pub fn query_quote(ticker: &str) -> Option<Quote> {
+    let connection = Connection::session().ok()?;
+    let reply = connection
+        .call_method(
+            Some("org.stocker.Quotes"),
+            "/org/stocker/Quotes",
+            Some("org.stocker.Quotes"),
+            "GetQuote",
+            &(ticker,),
+        )
+        .ok()?;
+    let (last, prev_close) = reply.body().deserialize::<(f64, f64)>().ok()?;
+    Some(Quote {
+        ticker: ticker.to_string(),
+        last,
+        prev_close,
+        // The bus interface only carries `(last, prev_close)`, same
+        // limitation `after_hours` already has here.
+        after_hours: None,
+        open: None,
+        volume: None,
+        day_high: None,
+        day_low: None,
+        bid: None,
+        ask: None,
+        last_trade_time: None,
+        source: None,
+    })
+}
+
+/// Emits `QuoteUpdated` for `quote` over `connection`.
+pub fn notify_quote_updated(connection: &Connection, quote: &Quote) -> Result<()> {
+    let iface_ref = connection
+        .object_server()
+        .interface::<_, Quotes>("/org/stocker/Quotes")
+        .context("looking up the org.stocker.Quotes interface")?;
+    block_on(Quotes::quote_updated(
+        iface_ref.signal_emitter(),
+        &quote.ticker,
+        quote.last,
+        quote.prev_close,
+    ))
+    .context("emitting QuoteUpdated")
+}
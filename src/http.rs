@@ -0,0 +1,73 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::{Context, Result};
+use tiny_http::{Header, Response, Server};
+
+use crate::config::Config;
+use crate::ipc::{self, DaemonState};
+use crate::quote::Quote;
+
+/// Starts a background thread serving `GET /quote/<ticker>` and `GET /all`
+/// as JSON on `127.0.0.1:<port>` (see `Config::http_port`), for local tools
+/// (eww `listen`, custom dashboards) that would rather poll plain HTTP than
+/// speak D-Bus. The bind failing is reported to the caller rather than
+/// silently disabled, since the user explicitly opted in by setting a port.
+pub fn serve(port: u16, state: Arc<Mutex<DaemonState>>, config: Arc<Config>) -> Result<()> {
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|err| anyhow::anyhow!("{err}"))
+        .with_context(|| format!("binding HTTP endpoint on 127.0.0.1:{port}"))?;
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = route(request.url(), &state, &config);
+            let _ = request.respond(response);
+        }
+    });
+    Ok(())
+}
+
+fn route(
+    url: &str,
+    state: &Arc<Mutex<DaemonState>>,
+    config: &Config,
+) -> Response<std::io::Cursor<Vec<u8>>> {
+    if let Some(ticker) = url.strip_prefix("/quote/") {
+        match ipc::lookup_quote(state, config, ticker) {
+            Some(quote) => json_response(&quote, 200),
+            None => json_response(&Error::new(format!("no cached quote for {ticker}")), 404),
+        }
+    } else if url == "/all" {
+        match config.active_tickers(None) {
+            Ok(entries) => {
+                let quotes: Vec<Quote> = entries
+                    .iter()
+                    .filter_map(|entry| ipc::lookup_quote(state, config, entry.symbol()))
+                    .collect();
+                json_response(&quotes, 200)
+            }
+            Err(err) => json_response(&Error::new(err.to_string()), 500),
+        }
+    } else {
+        json_response(&Error::new("not found".to_string()), 404)
+    }
+}
+
+#[derive(serde::Serialize)]
+struct Error {
+    error: String,
+}
+
+impl Error {
+    fn new(error: String) -> Self {
+        Error { error }
+    }
+}
+
+fn json_response<T: serde::Serialize>(body: &T, status: u16) -> Response<std::io::Cursor<Vec<u8>>> {
+    let json = serde_json::to_string(body).expect("response body is always serializable");
+    let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("static header is always valid");
+    Response::from_string(json)
+        .with_status_code(status)
+        .with_header(header)
+}
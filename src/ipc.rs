@@ -0,0 +1,174 @@
+use std::ffi::CString;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use listenfd::ListenFd;
+
+use crate::quote::Quote;
+
+fn runtime_dir() -> PathBuf {
+    let dir = std::env::var("XDG_RUNTIME_DIR").unwrap_or_else(|_| "/tmp".to_string());
+    PathBuf::from(dir)
+}
+
+/// `$XDG_RUNTIME_DIR/stocker.sock`, falling back to `/tmp/stocker.sock` when
+/// `XDG_RUNTIME_DIR` isn't set.
+pub fn socket_path() -> PathBuf {
+    runtime_dir().join("stocker.sock")
+}
+
+/// `$XDG_RUNTIME_DIR/stocker.fifo`, falling back to `/tmp/stocker.fifo`.
+pub fn fifo_path() -> PathBuf {
+    runtime_dir().join("stocker.fifo")
+}
+
+/// Runtime state a `stocker daemon` exposes to `stocker ctl`, shared between
+/// the rotation loop and the socket listener thread.
+#[derive(Default)]
+pub struct DaemonState {
+    /// Which ticker in the active watchlist is currently shown. Only ever
+    /// read modulo the watchlist length, so `next`/`prev` can move it
+    /// without the listener thread knowing how many tickers there are.
+    pub index: i64,
+    pub paused: bool,
+    /// Set by `show <ticker>`; consumed by the rotation loop to jump to that
+    /// symbol instead of advancing normally.
+    pub show: Option<String>,
+    /// Set by `refresh`; consumed by the rotation loop to bypass the cache
+    /// for the next tick.
+    pub force_refresh: bool,
+    /// Set whenever a command arrives, so the rotation loop's sleep wakes up
+    /// immediately instead of waiting out the rest of `rotation_seconds`.
+    pub wake: bool,
+    /// The quote currently shown, kept here so the waybar click handler can
+    /// open the right ticker's page without re-fetching it.
+    pub current_quote: Option<Quote>,
+}
+
+/// Starts a background thread listening for `stocker ctl` connections,
+/// applying each command line to `state` and waking `condvar` so the
+/// daemon's rotation loop reacts immediately. Uses the socket systemd
+/// already bound (`LISTEN_FDS`) when launched via socket activation,
+/// otherwise binds `socket_path()` itself.
+pub fn serve(state: Arc<Mutex<DaemonState>>, condvar: Arc<Condvar>) -> Result<()> {
+    let listener = match ListenFd::from_env().take_unix_listener(0)? {
+        Some(listener) => listener,
+        None => {
+            let path = socket_path();
+            let _ = fs::remove_file(&path);
+            UnixListener::bind(&path)
+                .with_context(|| format!("binding control socket {}", path.display()))?
+        }
+    };
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            handle_connection(stream, &state, &condvar);
+        }
+    });
+    Ok(())
+}
+
+fn handle_connection(stream: UnixStream, state: &Arc<Mutex<DaemonState>>, condvar: &Arc<Condvar>) {
+    let mut line = String::new();
+    if BufReader::new(stream).read_line(&mut line).is_err() {
+        return;
+    }
+    apply_command(&line, state, condvar);
+}
+
+/// Applies one command line (`next`, `prev`, `refresh`, `toggle`, or
+/// `show <ticker>`) to `state` and wakes `condvar`, shared by both the
+/// control socket and the FIFO listener.
+fn apply_command(line: &str, state: &Arc<Mutex<DaemonState>>, condvar: &Arc<Condvar>) {
+    let mut state = state.lock().unwrap();
+    match line.trim() {
+        "next" => state.index = state.index.wrapping_add(1),
+        "prev" => state.index = state.index.wrapping_sub(1),
+        "refresh" => state.force_refresh = true,
+        "toggle" => state.paused = !state.paused,
+        other => {
+            if let Some(ticker) = other.strip_prefix("show ") {
+                state.show = Some(ticker.trim().to_string());
+            }
+        }
+    }
+    state.wake = true;
+    condvar.notify_one();
+}
+
+/// Creates `fifo_path()` (if it doesn't already exist) and starts a
+/// background thread reading command lines from it, applying them the same
+/// way `stocker ctl` commands are — a lighter alternative to the control
+/// socket for callers that just want `echo next > $XDG_RUNTIME_DIR/stocker.fifo`
+/// from a sway/i3 keybinding, with no client program required.
+pub fn serve_fifo(state: Arc<Mutex<DaemonState>>, condvar: Arc<Condvar>) -> Result<()> {
+    let path = fifo_path();
+    let c_path = CString::new(path.as_os_str().as_encoded_bytes())
+        .with_context(|| format!("{} is not a valid FIFO path", path.display()))?;
+    // SAFETY: `c_path` is a valid, NUL-terminated C string for the lifetime
+    // of this call. `mkfifo` fails harmlessly (EEXIST) if a stale FIFO from
+    // a previous run is already there, which we treat as success below.
+    let result = unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) };
+    if result != 0 {
+        let err = std::io::Error::last_os_error();
+        if err.kind() != std::io::ErrorKind::AlreadyExists {
+            return Err(err).with_context(|| format!("creating FIFO {}", path.display()));
+        }
+    }
+    thread::spawn(move || loop {
+        // A FIFO reader sees EOF once every writer closes it, so it must be
+        // reopened after each EOF to keep listening for the next `echo`.
+        let file = match fs::File::open(&path) {
+            Ok(file) => file,
+            Err(_) => return,
+        };
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            apply_command(&line, &state, &condvar);
+        }
+    });
+    Ok(())
+}
+
+/// Looks up a ticker's quote: the daemon's in-memory current quote if it
+/// matches, else the on-disk cache regardless of its TTL — a stale-but-
+/// present quote is more useful to a caller (D-Bus, the HTTP endpoint) than
+/// an error.
+pub fn lookup_quote(
+    state: &Arc<Mutex<DaemonState>>,
+    config: &crate::config::Config,
+    ticker: &str,
+) -> Option<Quote> {
+    let current = state.lock().unwrap().current_quote.clone();
+    current
+        .filter(|quote| quote.ticker.eq_ignore_ascii_case(ticker))
+        .or_else(|| {
+            crate::cache::read(config.cache_dir.as_deref(), ticker, Duration::MAX)
+                .map(|(quote, _age)| quote)
+        })
+}
+
+/// Whether a control socket is already live, i.e. another `stocker daemon`
+/// is already running — used so a second bar instance (multi-monitor
+/// waybar) doesn't start a competing daemon that would steal the socket.
+pub fn is_daemon_running() -> bool {
+    UnixStream::connect(socket_path()).is_ok()
+}
+
+/// Sends one command line to a running daemon's control socket.
+pub fn send(command: &str) -> Result<()> {
+    let path = socket_path();
+    let mut stream = UnixStream::connect(&path).with_context(|| {
+        format!(
+            "connecting to {} — is `stocker daemon` running?",
+            path.display()
+        )
+    })?;
+    writeln!(stream, "{command}")?;
+    Ok(())
+}
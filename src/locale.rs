@@ -0,0 +1,143 @@
+/// Which characters separate the integer part's thousands groups and the
+/// fractional part, e.g. US `1,234.56` versus German `1.234,56`. Not a full
+/// locale implementation -- just the decimal/thousands swap most requests
+/// for this actually mean, since prices otherwise always render with a
+/// hardcoded `.` regardless of the user's own locale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberFormat {
+    decimal: char,
+    thousands: char,
+}
+
+impl NumberFormat {
+    const US: NumberFormat = NumberFormat {
+        decimal: '.',
+        thousands: ',',
+    };
+    const EUROPEAN: NumberFormat = NumberFormat {
+        decimal: ',',
+        thousands: '.',
+    };
+
+    /// Formats `value` to `precision` decimal places using this format's
+    /// separators, e.g. `1234.5` at precision 2 renders as `"1.234,50"`
+    /// under `EUROPEAN`. `force_sign` mirrors the `{:+}` format spec, for
+    /// the `+1,23%`-style fields a `-` alone wouldn't read as "up".
+    pub fn format(&self, value: f64, precision: usize, force_sign: bool) -> String {
+        let formatted = if force_sign {
+            format!("{value:+.precision$}")
+        } else {
+            format!("{value:.precision$}")
+        };
+        let (sign, unsigned) = match formatted.chars().next() {
+            Some('+' | '-') => (&formatted[..1], &formatted[1..]),
+            _ => ("", formatted.as_str()),
+        };
+        let (int_part, frac_part) = match unsigned.split_once('.') {
+            Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+            None => (unsigned, None),
+        };
+        let grouped = group_thousands(int_part, self.thousands);
+        match frac_part {
+            Some(frac_part) => format!("{sign}{grouped}{}{frac_part}", self.decimal),
+            None => format!("{sign}{grouped}"),
+        }
+    }
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self::US
+    }
+}
+
+fn group_thousands(digits: &str, sep: char) -> String {
+    let len = digits.len();
+    let mut out = String::with_capacity(len + len / 3);
+    for (i, ch) in digits.chars().enumerate() {
+        if i != 0 && (len - i).is_multiple_of(3) {
+            out.push(sep);
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Resolves a `locale` config value, falling back to `LC_NUMERIC`/`LC_ALL`
+/// (in that order) if unset, to a `NumberFormat` -- accepts either a bare
+/// language code or a full POSIX locale string like `de_DE.UTF-8`. Anything
+/// unrecognized (including `"C"`/`"POSIX"`) falls back to `US`, matching
+/// this crate's formatting before locale support existed.
+pub fn resolve(locale: Option<&str>) -> NumberFormat {
+    let locale = locale
+        .map(str::to_string)
+        .or_else(|| std::env::var("LC_NUMERIC").ok())
+        .or_else(|| std::env::var("LC_ALL").ok());
+    match locale {
+        Some(locale) if uses_comma_decimal(&locale) => NumberFormat::EUROPEAN,
+        _ => NumberFormat::US,
+    }
+}
+
+/// Languages whose common convention uses a comma as the decimal separator
+/// (and a `.`/space/`'` for thousands, which `EUROPEAN` approximates with
+/// `.`). Far from exhaustive, but covers the locales users actually hit
+/// this setting for.
+fn uses_comma_decimal(locale: &str) -> bool {
+    let language = locale
+        .split(['_', '-', '.'])
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+    matches!(
+        language.as_str(),
+        "de" | "fr"
+            | "es"
+            | "it"
+            | "pt"
+            | "nl"
+            | "ru"
+            | "pl"
+            | "tr"
+            | "da"
+            | "fi"
+            | "nb"
+            | "nn"
+            | "sv"
+            | "cs"
+            | "sk"
+            | "el"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn european_format_swaps_separators() {
+        assert_eq!(NumberFormat::EUROPEAN.format(1234.5, 2, false), "1.234,50");
+    }
+
+    #[test]
+    fn us_format_keeps_the_hardcoded_defaults() {
+        assert_eq!(NumberFormat::US.format(1234.5, 2, false), "1,234.50");
+    }
+
+    #[test]
+    fn force_sign_keeps_the_sign_before_the_grouped_digits() {
+        assert_eq!(NumberFormat::EUROPEAN.format(-1234.5, 2, true), "-1.234,50");
+        assert_eq!(NumberFormat::EUROPEAN.format(1234.5, 2, true), "+1.234,50");
+    }
+
+    #[test]
+    fn de_de_locale_string_resolves_to_european() {
+        assert_eq!(resolve(Some("de_DE.UTF-8")), NumberFormat::EUROPEAN);
+    }
+
+    #[test]
+    fn unrecognized_locale_falls_back_to_us() {
+        assert_eq!(resolve(Some("C")), NumberFormat::US);
+        assert_eq!(resolve(Some("en_US.UTF-8")), NumberFormat::US);
+    }
+}
@@ -0,0 +1,1200 @@
+mod cache;
+mod cli;
+mod click;
+mod config;
+mod currency;
+mod dbus;
+mod http;
+mod ipc;
+mod locale;
+mod numfmt;
+mod output;
+mod provider;
+mod quote;
+mod secret;
+mod signals;
+mod sparkline;
+mod ticker;
+mod tui;
+mod watchdog;
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+
+use cli::{CacheCommand, Cli, Command, ConfigCommand, CtlCommand};
+use config::Config;
+use ipc::DaemonState;
+use output::Format;
+use quote::Quote;
+
+fn main() -> Result<()> {
+    let mut cli = Cli::parse();
+    let format = if cli.plain {
+        Format::Plain
+    } else {
+        Format::from_name(&cli.format)?
+    };
+
+    let ticker_override = cli.ticker.take();
+    let command = cli.command.take().unwrap_or(Command::Quote {
+        ticker: ticker_override,
+    });
+    match command {
+        Command::Quote {
+            ticker: Some(ticker),
+        } => run_single_quote(&cli, format, &ticker),
+        Command::Quote { ticker: None } => run_watchlist(&cli, format),
+        Command::List => run_list(&cli),
+        Command::Config {
+            action: ConfigCommand::Validate { check_provider },
+        } => run_config_validate(&cli, check_provider),
+        Command::Config {
+            action: ConfigCommand::Init { force },
+        } => run_config_init(&cli, force),
+        Command::Config {
+            action: ConfigCommand::Schema,
+        } => run_config_schema(),
+        Command::Cache {
+            action: CacheCommand::Ls,
+        } => run_cache_ls(&cli),
+        Command::Cache {
+            action: CacheCommand::Clear { ticker },
+        } => run_cache_clear(&cli, ticker),
+        Command::Cache {
+            action: CacheCommand::Gc { older_than },
+        } => run_cache_gc(&cli, older_than),
+        Command::Prefetch => run_prefetch(&cli),
+        Command::Tui => {
+            let config = Config::load(&config_path(&cli))?;
+            let watchlist = cli.watchlist.clone();
+            let verbose = cli.verbose;
+            tui::run(move || fetch_all(&config, watchlist.as_deref(), verbose))
+        }
+        Command::Daemon => run_daemon(&cli, format),
+        Command::Ctl { action } => run_ctl(action),
+    }
+}
+
+/// Builds the render options for one ticker: display flags and templates
+/// come from the global config, while thresholds/precision/display_name
+/// come from that ticker's own entry (or its defaults for a bare ticker).
+fn options<'a>(
+    cli: &Cli,
+    config: &'a Config,
+    entry: Option<&'a config::TickerEntry>,
+) -> output::Options<'a> {
+    output::Options {
+        pango: config.markup.as_deref() == Some("pango"),
+        color: cli.color,
+        text_template: config.text_template.as_deref(),
+        tooltip_template: config.tooltip_template.as_deref(),
+        thresholds: entry.map(|e| e.thresholds()).unwrap_or_default(),
+        precision: config.precision_for(entry.and_then(|e| e.precision())),
+        display_name: entry
+            .and_then(|e| e.display_name())
+            .or_else(|| entry.and_then(|e| config.alias_for(e.symbol()))),
+        paused: false,
+        stale: false,
+        market_status: config
+            .market_status_for(entry.and_then(|e| e.market_hours()), SystemTime::now()),
+        show_after_hours: config.show_after_hours.unwrap_or(false),
+        currency: currency::symbol(entry.and_then(|e| e.currency()).unwrap_or("USD")),
+        // Resolved once the quote is in hand, since `Config::baseline_price`
+        // needs `quote.open` -- see each call site.
+        percent_change_baseline: None,
+        // Resolved once the ticker symbol is in hand -- see each call site.
+        sparkline: None,
+        glyphs: config.glyphs.as_ref(),
+        icon: entry.and_then(|e| e.icon()),
+        show_absolute_change: config.show_absolute_change.unwrap_or(false),
+        compact_number_threshold: config.compact_threshold(),
+        // Resolved once the ticker symbol is in hand -- see each call site.
+        week52_range: None,
+        show_spread: config.show_spread.unwrap_or(false),
+        // Resolved once the active watchlist is in hand -- see each call site.
+        watchlist_summary: None,
+        number_format: locale::resolve(config.locale.as_deref()),
+        color_gradient: config.show_color_gradient.unwrap_or(false),
+        class_names: config.class_names.as_ref(),
+        text_width: config.text_width,
+        // Resolved once the ticker symbol is in hand -- see each call site.
+        market_cap: None,
+        previous_class: None,
+    }
+}
+
+/// Renders `ticker`'s sparkline from the last 24 hours of cached prices, if
+/// `show_sparkline` is on and the cache has more than a single sample --
+/// silently `None` otherwise (a brand new ticker, or one with a cleared
+/// cache, just doesn't get a sparkline yet).
+fn sparkline_for(config: &Config, ticker: &str) -> Option<String> {
+    if !config.show_sparkline.unwrap_or(false) {
+        return None;
+    }
+    let since = SystemTime::now().checked_sub(Duration::from_secs(24 * 60 * 60))?;
+    let prices = cache::history_since(config.cache_dir.as_deref(), ticker, since).ok()?;
+    let spark = sparkline::render(&prices);
+    (!spark.is_empty()).then_some(spark)
+}
+
+/// Looks up `symbol`'s 52-week `(low, high)` range via a synthetic
+/// `52w:<symbol>` entry, if `show_52_week_range` is on -- reusing
+/// `fetch_ticker`'s usual cache/TTL handling the same way `convert_to_base`
+/// does for FX rates. `None` if the setting is off or the lookup fails (no
+/// `tiingo-52w`-capable `api_key`, network error, etc). `divisor`, if set, is
+/// the same `unit_divisor` applied to the ticker's own quote, and
+/// `ticker_currency` is run through the same `convert_to_base` conversion --
+/// the range comes from a separate fetch, so it needs both applied by hand
+/// rather than inheriting them from the ticker's own quote.
+fn week52_range_for(
+    config: &Config,
+    symbol: &str,
+    ticker_currency: &str,
+    verbose: bool,
+    divisor: Option<f64>,
+) -> Option<(f64, f64)> {
+    if !config.show_52_week_range.unwrap_or(false) {
+        return None;
+    }
+    let synthetic = config::TickerEntry::Plain(format!("52w:{symbol}"));
+    let (quote, ..) = fetch_ticker(config, &synthetic, verbose, None).ok()?;
+    let divisor = divisor.unwrap_or(1.0);
+    let range_quote = Quote {
+        last: quote.last / divisor,
+        prev_close: quote.prev_close / divisor,
+        ..quote
+    };
+    let (range_quote, _) = convert_to_base(config, range_quote, ticker_currency, verbose);
+    Some((range_quote.prev_close, range_quote.last))
+}
+
+/// Looks up `symbol`'s market cap via a synthetic `mcap:<symbol>` entry, if
+/// `show_market_cap` is on -- same `fetch_ticker` reuse trick as
+/// `week52_range_for`. `None` if the setting is off or the lookup fails.
+fn market_cap_for(config: &Config, symbol: &str, verbose: bool) -> Option<f64> {
+    if !config.show_market_cap.unwrap_or(false) {
+        return None;
+    }
+    let synthetic = config::TickerEntry::Plain(format!("mcap:{symbol}"));
+    let (quote, ..) = fetch_ticker(config, &synthetic, verbose, None).ok()?;
+    Some(quote.last)
+}
+
+/// The threshold class `symbol` was last rendered as, for
+/// `output::class_with_hysteresis`. Prefers the in-memory cache (daemon
+/// mode) over the on-disk one, matching how `fetch_ticker` prefers it for
+/// quotes.
+fn previous_class_for(
+    config: &Config,
+    symbol: &str,
+    mem_cache: Option<&cache::MemCache>,
+) -> Option<String> {
+    mem_cache
+        .and_then(|mem_cache| mem_cache.read_class(symbol))
+        .or_else(|| cache::read_class(config.cache_dir.as_deref(), symbol))
+}
+
+/// Records `class` as `symbol`'s most recent threshold classification, so
+/// the next call's `previous_class_for` can apply hysteresis against it.
+fn record_class(config: &Config, symbol: &str, mem_cache: Option<&cache::MemCache>, class: &str) {
+    match mem_cache {
+        Some(mem_cache) => mem_cache.write_class(symbol, class),
+        None => {
+            let _ = cache::write_class(config.cache_dir.as_deref(), symbol, class);
+        }
+    }
+}
+
+/// Builds a `"<label>: <pct>%"` line per active ticker from whatever's
+/// already cached, if `show_watchlist_tooltip` is on -- deliberately reads
+/// the cache rather than calling `fetch_ticker`, so hovering never blocks on
+/// a network round trip for a ticker the rotation hasn't reached yet. A
+/// ticker with nothing cached is just left out rather than failing the
+/// whole summary.
+fn watchlist_summary_for(config: &Config, watchlist: Option<&str>) -> Option<String> {
+    if !config.show_watchlist_tooltip.unwrap_or(false) {
+        return None;
+    }
+    let entries = config.active_tickers(watchlist).ok()?;
+    let lines: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| {
+            let (_, symbol) = ticker::resolve(entry.symbol());
+            let (quote, _) = cache::read(config.cache_dir.as_deref(), symbol, Duration::MAX)?;
+            let baseline = config.baseline_price(&quote, entry.baseline(), entry.reference_price());
+            let pct = quote.percent_change_against(baseline);
+            let label = entry
+                .display_name()
+                .or_else(|| config.alias_for(entry.symbol()))
+                .unwrap_or(entry.symbol());
+            Some(format!("{label}: {pct:+.2}%"))
+        })
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Multiplies every one of `quote`'s price fields by `factor` -- the math
+/// shared by `apply_unit_divisor` (a fractional factor, dividing pence into
+/// pounds) and `convert_to_base` (an FX rate), so a price field added to
+/// `Quote` only needs to be listed here once instead of in both places.
+fn scale_quote_prices(quote: Quote, factor: f64) -> Quote {
+    Quote {
+        last: quote.last * factor,
+        prev_close: quote.prev_close * factor,
+        after_hours: quote.after_hours.map(|v| v * factor),
+        open: quote.open.map(|v| v * factor),
+        day_high: quote.day_high.map(|v| v * factor),
+        day_low: quote.day_low.map(|v| v * factor),
+        bid: quote.bid.map(|v| v * factor),
+        ask: quote.ask.map(|v| v * factor),
+        ..quote
+    }
+}
+
+/// Scales `quote`'s price fields down by `entry`'s `unit_divisor`, e.g. 100
+/// for a London-listed ticker quoted in pence, so everything downstream
+/// (currency conversion, percent-change baseline, display) works in the
+/// units `entry.currency()` implies rather than the provider's raw ones.
+/// A no-op if the entry didn't set one.
+fn apply_unit_divisor(quote: Quote, entry: &config::TickerEntry) -> Quote {
+    match entry.unit_divisor() {
+        Some(divisor) => scale_quote_prices(quote, 1.0 / divisor),
+        None => quote,
+    }
+}
+
+/// Converts `quote`'s prices from `ticker_currency` into `config.base_currency`,
+/// via a live exchange rate fetched (and cached) the same way any other
+/// quote is -- a synthetic `fx:<from><to>` entry gets `fetch_ticker`'s usual
+/// TTL/staleness handling for free. Returns the unconverted `quote` and
+/// `ticker_currency` unchanged if no `base_currency` is configured, the
+/// currencies already match, or the rate fetch fails -- a ticker shown in
+/// its own currency is a lot less surprising than a widget that stops
+/// working because one FX pair had a bad day.
+fn convert_to_base<'a>(
+    config: &'a Config,
+    quote: Quote,
+    ticker_currency: &'a str,
+    verbose: bool,
+) -> (Quote, &'a str) {
+    let Some(base_currency) = config.base_currency.as_deref() else {
+        return (quote, ticker_currency);
+    };
+    if ticker_currency.eq_ignore_ascii_case(base_currency) {
+        return (quote, ticker_currency);
+    }
+    let pair = format!(
+        "fx:{}{}",
+        ticker_currency.to_lowercase(),
+        base_currency.to_lowercase()
+    );
+    let synthetic = config::TickerEntry::Plain(pair);
+    match fetch_ticker(config, &synthetic, verbose, None) {
+        Ok((rate_quote, ..)) => {
+            let converted = scale_quote_prices(quote, rate_quote.last);
+            (converted, base_currency)
+        }
+        Err(err) => {
+            if verbose {
+                eprintln!("stocker: currency conversion to {base_currency} disabled: {err:#}");
+            }
+            (quote, ticker_currency)
+        }
+    }
+}
+
+fn run_watchlist(cli: &Cli, format: Format) -> Result<()> {
+    let config = Config::load(&config_path(cli))?;
+
+    if format.is_streaming() {
+        return run_streaming(format, &config, cli.watchlist.as_deref(), cli.verbose);
+    }
+
+    prime_cache(&config, cli.watchlist.as_deref(), cli.verbose);
+    for entry in config.active_tickers(cli.watchlist.as_deref())? {
+        let (quote, cache_age, stale) = fetch_ticker(&config, entry, cli.verbose, None)?;
+        let quote = apply_unit_divisor(quote, entry);
+        let (quote, display_currency) = convert_to_base(
+            &config,
+            quote,
+            entry.currency().unwrap_or("USD"),
+            cli.verbose,
+        );
+        let mut options = options(cli, &config, Some(entry));
+        options.stale = stale;
+        options.percent_change_baseline =
+            Some(config.baseline_price(&quote, entry.baseline(), entry.reference_price()));
+        options.currency = currency::symbol(display_currency);
+        options.sparkline = sparkline_for(&config, entry.symbol());
+        options.week52_range = week52_range_for(
+            &config,
+            entry.symbol(),
+            entry.currency().unwrap_or("USD"),
+            cli.verbose,
+            entry.unit_divisor(),
+        );
+        options.market_cap = market_cap_for(&config, entry.symbol(), cli.verbose);
+        options.watchlist_summary = watchlist_summary_for(&config, cli.watchlist.as_deref());
+        options.previous_class = previous_class_for(&config, entry.symbol(), None);
+        let pct = options.pct(&quote);
+        let resolved_class = output::class_with_hysteresis(
+            pct,
+            options.thresholds,
+            options.paused,
+            options.stale,
+            options.previous_class.as_deref(),
+        );
+        record_class(&config, entry.symbol(), None, resolved_class);
+        println!("{}", output::render(format, &quote, &options, cache_age)?);
+    }
+
+    Ok(())
+}
+
+/// Never exits: shows one ticker from the active watchlist at a time,
+/// advancing to the next on every `rotation_seconds` tick, so waybar can run
+/// this once as a continuous `exec` module instead of spawning a fresh
+/// process (and re-doing TLS handshakes) every interval. Also opens a
+/// control socket (see [`ipc`]) so `stocker ctl` can drive the rotation,
+/// installs handlers so `SIGUSR1`/`SIGUSR2` (see [`signals`]) can too, opens
+/// a FIFO at `$XDG_RUNTIME_DIR/stocker.fifo` for the same commands without
+/// needing a client program (see [`ipc::serve_fifo`]), and reads waybar
+/// click events from stdin (see [`click`]). Under a
+/// `Type=notify` systemd unit, also reports `READY=1` after the first
+/// successful quote and sends `WATCHDOG=1` heartbeats (see [`watchdog`]).
+/// Also publishes the `org.stocker.Quotes` session-bus service (see
+/// [`dbus`]), for widgets that would rather query it than the API — a
+/// missing session bus disables that service without failing the daemon —
+/// and, if `http_port` is configured, a plain JSON HTTP endpoint (see
+/// [`http`]).
+fn run_daemon(cli: &Cli, format: Format) -> Result<()> {
+    let config = Arc::new(Config::load(&config_path(cli))?);
+
+    if format.is_streaming() {
+        return run_streaming(format, &config, cli.watchlist.as_deref(), cli.verbose);
+    }
+
+    if ipc::is_daemon_running() {
+        return run_thin_client(cli, format, &config);
+    }
+
+    let state = Arc::new(Mutex::new(DaemonState::default()));
+    let condvar = Arc::new(Condvar::new());
+    ipc::serve(Arc::clone(&state), Arc::clone(&condvar))?;
+    ipc::serve_fifo(Arc::clone(&state), Arc::clone(&condvar))?;
+    signals::watch(Arc::clone(&state), Arc::clone(&condvar))?;
+    click::watch(
+        Arc::clone(&state),
+        Arc::clone(&condvar),
+        config.click_url_template.clone(),
+    );
+    watchdog::watch();
+    let dbus_connection = match dbus::serve(Arc::clone(&state), Arc::clone(&config)) {
+        Ok(connection) => Some(connection),
+        Err(err) => {
+            if cli.verbose {
+                eprintln!("stocker: D-Bus service disabled: {err:#}");
+            }
+            None
+        }
+    };
+    if let Some(port) = config.http_port {
+        http::serve(port, Arc::clone(&state), Arc::clone(&config))?;
+    }
+
+    let mem_cache = Arc::new(cache::MemCache::new());
+    spawn_mem_cache_flush(Arc::clone(&config), Arc::clone(&mem_cache));
+
+    prime_cache(&config, cli.watchlist.as_deref(), cli.verbose);
+    let mut stdout = io::stdout();
+    let mut ready_sent = false;
+    loop {
+        let tickers = config.active_tickers(cli.watchlist.as_deref())?;
+        anyhow::ensure!(!tickers.is_empty(), "no tickers configured");
+
+        let mut guard = state.lock().unwrap();
+        if let Some(symbol) = guard.show.take() {
+            if let Some(pos) = tickers
+                .iter()
+                .position(|entry| entry.symbol().eq_ignore_ascii_case(&symbol))
+            {
+                guard.index = pos as i64;
+            }
+        }
+        let len = tickers.len() as i64;
+        let index = (guard.index.rem_euclid(len)) as usize;
+        let force_refresh = std::mem::take(&mut guard.force_refresh);
+        let paused = guard.paused;
+        drop(guard);
+
+        let entry = &tickers[index];
+        if force_refresh {
+            cache::forget(config.cache_dir.as_deref(), entry.symbol())?;
+            mem_cache.forget(entry.symbol());
+        }
+        let (quote, cache_age, stale) =
+            fetch_ticker(&config, entry, cli.verbose, Some(&mem_cache))?;
+        let quote = apply_unit_divisor(quote, entry);
+        let (quote, display_currency) = convert_to_base(
+            &config,
+            quote,
+            entry.currency().unwrap_or("USD"),
+            cli.verbose,
+        );
+        let mut render_options = options(cli, &config, Some(entry));
+        render_options.paused = paused;
+        render_options.stale = stale;
+        render_options.percent_change_baseline =
+            Some(config.baseline_price(&quote, entry.baseline(), entry.reference_price()));
+        render_options.currency = currency::symbol(display_currency);
+        render_options.sparkline = sparkline_for(&config, entry.symbol());
+        render_options.week52_range = week52_range_for(
+            &config,
+            entry.symbol(),
+            entry.currency().unwrap_or("USD"),
+            cli.verbose,
+            entry.unit_divisor(),
+        );
+        render_options.market_cap = market_cap_for(&config, entry.symbol(), cli.verbose);
+        render_options.watchlist_summary = watchlist_summary_for(&config, cli.watchlist.as_deref());
+        render_options.previous_class =
+            previous_class_for(&config, entry.symbol(), Some(&mem_cache));
+        let pct = render_options.pct(&quote);
+        let resolved_class = output::class_with_hysteresis(
+            pct,
+            render_options.thresholds,
+            render_options.paused,
+            render_options.stale,
+            render_options.previous_class.as_deref(),
+        );
+        record_class(&config, entry.symbol(), Some(&mem_cache), resolved_class);
+        println!(
+            "{}",
+            output::render(format, &quote, &render_options, cache_age)?
+        );
+        stdout.flush()?;
+        if let Some(connection) = &dbus_connection {
+            let _ = dbus::notify_quote_updated(connection, &quote);
+        }
+        state.lock().unwrap().current_quote = Some(quote.clone());
+        if !ready_sent {
+            watchdog::notify_ready();
+            ready_sent = true;
+        }
+
+        // Reprint the same quote at `print_interval` until a full
+        // `rotation_interval` has passed (or a command wakes us early), so
+        // e.g. a "last updated Ns ago" tooltip keeps ticking between
+        // fetches instead of only updating once per rotation.
+        let fetched_at = Instant::now();
+        let rotation_interval = config.rotation_interval();
+        let print_interval = config.print_interval();
+        loop {
+            let guard = state.lock().unwrap();
+            let (mut guard, _timeout) = condvar
+                .wait_timeout_while(guard, print_interval, |state| !state.wake)
+                .unwrap();
+            let woken_by_command = guard.wake;
+            guard.wake = false;
+            if woken_by_command {
+                drop(guard);
+                break;
+            }
+            let elapsed = fetched_at.elapsed();
+            if elapsed >= rotation_interval {
+                if !paused {
+                    guard.index = guard.index.wrapping_add(1);
+                }
+                drop(guard);
+                break;
+            }
+            drop(guard);
+
+            let mut render_options = options(cli, &config, Some(entry));
+            render_options.paused = paused;
+            render_options.stale = stale;
+            render_options.percent_change_baseline =
+                Some(config.baseline_price(&quote, entry.baseline(), entry.reference_price()));
+            render_options.currency = currency::symbol(display_currency);
+            render_options.sparkline = sparkline_for(&config, entry.symbol());
+            render_options.week52_range = week52_range_for(
+                &config,
+                entry.symbol(),
+                entry.currency().unwrap_or("USD"),
+                cli.verbose,
+                entry.unit_divisor(),
+            );
+            render_options.market_cap = market_cap_for(&config, entry.symbol(), cli.verbose);
+            render_options.watchlist_summary =
+                watchlist_summary_for(&config, cli.watchlist.as_deref());
+            render_options.previous_class =
+                previous_class_for(&config, entry.symbol(), Some(&mem_cache));
+            println!(
+                "{}",
+                output::render(format, &quote, &render_options, cache_age + elapsed)?
+            );
+            stdout.flush()?;
+        }
+    }
+}
+
+/// Runs alongside an already-running `stocker daemon` (e.g. a second bar on
+/// a multi-monitor setup): instead of fetching and caching its own
+/// watchlist, it asks the existing daemon for each ticker over D-Bus (see
+/// [`dbus::query_quote`]) so dual-head setups don't double the API usage,
+/// falling back to `fetch_ticker` for anything the primary daemon hasn't
+/// cached yet. Has no control socket of its own, so `stocker ctl` always
+/// reaches the one daemon actually driving the rotation.
+fn run_thin_client(cli: &Cli, format: Format, config: &Config) -> Result<()> {
+    let mut stdout = io::stdout();
+    let mut index: usize = 0;
+    loop {
+        let tickers = config.active_tickers(cli.watchlist.as_deref())?;
+        anyhow::ensure!(!tickers.is_empty(), "no tickers configured");
+        let entry = &tickers[index % tickers.len()];
+
+        // A D-Bus hit already went through `run_daemon`'s own
+        // `apply_unit_divisor`/`convert_to_base` before being published, so
+        // redoing either here would divide and FX-convert it a second time.
+        let (quote, cache_age, stale, already_converted) = match dbus::query_quote(entry.symbol()) {
+            Some(quote) => (quote, Duration::ZERO, false, true),
+            None => {
+                let (quote, cache_age, stale) = fetch_ticker(config, entry, cli.verbose, None)?;
+                (apply_unit_divisor(quote, entry), cache_age, stale, false)
+            }
+        };
+        let (quote, display_currency) = if already_converted {
+            let currency = config
+                .base_currency
+                .as_deref()
+                .unwrap_or_else(|| entry.currency().unwrap_or("USD"));
+            (quote, currency)
+        } else {
+            convert_to_base(
+                config,
+                quote,
+                entry.currency().unwrap_or("USD"),
+                cli.verbose,
+            )
+        };
+        let mut options = options(cli, config, Some(entry));
+        options.stale = stale;
+        options.percent_change_baseline =
+            Some(config.baseline_price(&quote, entry.baseline(), entry.reference_price()));
+        options.currency = currency::symbol(display_currency);
+        options.sparkline = sparkline_for(config, entry.symbol());
+        options.week52_range = week52_range_for(
+            config,
+            entry.symbol(),
+            entry.currency().unwrap_or("USD"),
+            cli.verbose,
+            entry.unit_divisor(),
+        );
+        options.market_cap = market_cap_for(config, entry.symbol(), cli.verbose);
+        options.watchlist_summary = watchlist_summary_for(config, cli.watchlist.as_deref());
+        options.previous_class = previous_class_for(config, entry.symbol(), None);
+        let pct = options.pct(&quote);
+        let resolved_class = output::class_with_hysteresis(
+            pct,
+            options.thresholds,
+            options.paused,
+            options.stale,
+            options.previous_class.as_deref(),
+        );
+        record_class(config, entry.symbol(), None, resolved_class);
+        println!("{}", output::render(format, &quote, &options, cache_age)?);
+        stdout.flush()?;
+
+        index = index.wrapping_add(1);
+        thread::sleep(config.rotation_interval());
+    }
+}
+
+/// Relays a `stocker ctl` subcommand to a running daemon's control socket.
+fn run_ctl(action: CtlCommand) -> Result<()> {
+    let command = match action {
+        CtlCommand::Next => "next".to_string(),
+        CtlCommand::Prev => "prev".to_string(),
+        CtlCommand::Refresh => "refresh".to_string(),
+        CtlCommand::Pause => "toggle".to_string(),
+        CtlCommand::Show { ticker } => format!("show {ticker}"),
+    };
+    ipc::send(&command)
+}
+
+/// Fetches a single ticker outside the configured watchlist, through the
+/// config's API key and provider fallback chain (or the provider a ticker
+/// prefix names), and renders it with the config's global settings; no
+/// watchlist entry's per-ticker settings apply.
+fn run_single_quote(cli: &Cli, format: Format, ticker: &str) -> Result<()> {
+    let config = Config::load(&config_path(cli))?;
+    let (prefix_override, symbol) = ticker::resolve(ticker);
+
+    let quote = match prefix_override {
+        Some(name) => fetch(name, config.api_key.as_deref(), symbol)?,
+        None => fetch_with_fallback(&config.provider_chain(), config.api_key.as_deref(), symbol)?,
+    };
+    let (quote, display_currency) = convert_to_base(&config, quote, "USD", cli.verbose);
+    let mut options = options(cli, &config, None);
+    options.percent_change_baseline = Some(config.baseline_price(&quote, None, None));
+    options.currency = currency::symbol(display_currency);
+    options.sparkline = sparkline_for(&config, symbol);
+    options.week52_range = week52_range_for(&config, symbol, "USD", cli.verbose, None);
+    options.market_cap = market_cap_for(&config, symbol, cli.verbose);
+    options.previous_class = previous_class_for(&config, symbol, None);
+    let pct = options.pct(&quote);
+    let resolved_class = output::class_with_hysteresis(
+        pct,
+        options.thresholds,
+        options.paused,
+        options.stale,
+        options.previous_class.as_deref(),
+    );
+    record_class(&config, symbol, None, resolved_class);
+    println!(
+        "{}",
+        output::render(format, &quote, &options, Duration::ZERO)?
+    );
+    Ok(())
+}
+
+/// Fetches and caches every ticker in the active watchlist, printing nothing
+/// per ticker -- just a final count -- so a systemd timer or login script
+/// doesn't spam a log with quote lines nobody reads. `stocker cache ls`
+/// afterward shows what got warmed.
+fn run_prefetch(cli: &Cli) -> Result<()> {
+    let config = Config::load(&config_path(cli))?;
+    let tickers = config.active_tickers(cli.watchlist.as_deref())?;
+    prime_cache(&config, cli.watchlist.as_deref(), cli.verbose);
+    for entry in tickers {
+        fetch_ticker(&config, entry, cli.verbose, None)?;
+    }
+    println!("prefetched {} ticker(s)", tickers.len());
+    Ok(())
+}
+
+fn run_list(cli: &Cli) -> Result<()> {
+    let config = Config::load(&config_path(cli))?;
+    for entry in config.active_tickers(cli.watchlist.as_deref())? {
+        match entry.provider() {
+            Some(provider) => println!("{} ({provider})", entry.symbol()),
+            None => println!("{}", entry.symbol()),
+        }
+    }
+    Ok(())
+}
+
+/// Parses the config, checks that it's internally consistent, and
+/// (optionally) confirms each ticker can actually be fetched, so a bad
+/// config or API key is caught here instead of failing later inside waybar.
+fn run_config_validate(cli: &Cli, check_provider: bool) -> Result<()> {
+    let path = config_path(cli);
+    let config = Config::load(&path)?;
+
+    let watchlists = std::iter::once(("tickers".to_string(), &config.tickers)).chain(
+        config
+            .watchlists
+            .iter()
+            .flatten()
+            .map(|(name, tickers)| (name.clone(), tickers)),
+    );
+    let mut any_tickers = false;
+    for (name, tickers) in watchlists {
+        for entry in tickers {
+            any_tickers = true;
+            anyhow::ensure!(
+                !entry.symbol().trim().is_empty(),
+                "{name}: a ticker entry is blank"
+            );
+            anyhow::ensure!(
+                entry.thresholds().is_ordered(),
+                "{name}: {}: thresholds must satisfy critdown < down < wayup, flat >= 0, hysteresis >= 0, and critical < critdown",
+                entry.symbol()
+            );
+            if check_provider {
+                fetch_ticker(&config, entry, cli.verbose, None).with_context(|| {
+                    format!("{name}: {}: provider check failed", entry.symbol())
+                })?;
+            }
+        }
+    }
+    anyhow::ensure!(any_tickers, "no tickers configured");
+
+    println!("{} is valid", path.display());
+    Ok(())
+}
+
+/// A commented starter config, covering the fields a new setup actually
+/// needs; everything else is left to its documented default.
+const STARTER_CONFIG: &str = r#"# stocker config. The doc comments on `Config` in src/config.rs list every field.
+
+# Uncomment one of these to enable a paid provider; omit all three to use
+# the keyless Stooq backend (delayed EOD data only).
+# api_key = "your-tiingo-token"
+# api_key_cmd = "pass show tiingo/api"
+# api_key_secret = "stocker/tiingo"
+
+tickers = [
+    "AAPL",
+    "MSFT",
+]
+"#;
+
+/// Writes [`STARTER_CONFIG`] to the effective config path, refusing to
+/// clobber an existing file unless `--force` was given.
+fn run_config_init(cli: &Cli, force: bool) -> Result<()> {
+    let path = config_path(cli);
+    anyhow::ensure!(
+        force || !path.exists(),
+        "{} already exists; pass --force to overwrite",
+        path.display()
+    );
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("creating config directory {}", dir.display()))?;
+    }
+    std::fs::write(&path, STARTER_CONFIG)
+        .with_context(|| format!("writing config file {}", path.display()))?;
+    println!("wrote starter config to {}", path.display());
+    Ok(())
+}
+
+/// The effective config path: `--config` if given, else the XDG default.
+fn config_path(cli: &Cli) -> PathBuf {
+    cli.config.clone().unwrap_or_else(Config::default_path)
+}
+
+/// Prints a JSON Schema for the config format to stdout, e.g. for a
+/// `"$schema"`-aware editor or a `yaml-language-server` comment.
+fn run_config_schema() -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&Config::json_schema())?);
+    Ok(())
+}
+
+/// Lists every ticker with a cached quote, its last known price, and how
+/// long ago that price was fetched.
+fn run_cache_ls(cli: &Cli) -> Result<()> {
+    let config = Config::load(&config_path(cli))?;
+    for (ticker, last, age) in cache::list(config.cache_dir.as_deref())? {
+        println!("{ticker}\t{last}\t{}s ago", age.as_secs());
+    }
+    Ok(())
+}
+
+fn run_cache_clear(cli: &Cli, ticker: Option<String>) -> Result<()> {
+    let config = Config::load(&config_path(cli))?;
+    let removed = match ticker {
+        Some(ticker) => cache::forget(config.cache_dir.as_deref(), &ticker)?,
+        None => cache::clear(config.cache_dir.as_deref())?,
+    };
+    println!("removed {removed} cached quote(s)");
+    Ok(())
+}
+
+/// Deletes cached rows older than `older_than`, e.g. left behind by a
+/// ticker that was since dropped from the watchlist.
+fn run_cache_gc(cli: &Cli, older_than: Duration) -> Result<()> {
+    let config = Config::load(&config_path(cli))?;
+    let removed = cache::gc(config.cache_dir.as_deref(), older_than)?;
+    println!("removed {removed} cached quote(s)");
+    Ok(())
+}
+
+/// Fetches the quote for one configured ticker, going through the cache
+/// first and falling back to the network on a miss. On a miss where a
+/// stale (past-TTL) quote is already cached, that stale quote is returned
+/// immediately — tagged with the `stale` flag — while a fresh one is
+/// fetched on a background thread and cached for next time, so a slow
+/// provider response never blocks the bar. Only a genuine first-ever fetch
+/// (nothing cached at all) blocks.
+///
+/// `mem_cache`, when given (only `stocker daemon` passes one), is checked
+/// before the on-disk cache and written to instead of it, so a long-running
+/// daemon revisiting a ticker it already has in memory skips the SQLite
+/// round trip entirely; [`cache::MemCache::flush`] is what eventually
+/// persists it.
+fn fetch_ticker(
+    config: &Config,
+    entry: &config::TickerEntry,
+    verbose: bool,
+    mem_cache: Option<&Arc<cache::MemCache>>,
+) -> Result<(Quote, Duration, bool)> {
+    let (prefix_override, symbol) = ticker::resolve(entry.symbol());
+    let provider_override = entry.provider().or(prefix_override);
+    let daily_only = ticker::is_daily_only(entry.symbol());
+    let ttl = config.cache_ttl_for(
+        daily_only,
+        entry.cache_seconds(),
+        entry.market_hours(),
+        entry.weekend_days(),
+        SystemTime::now(),
+    );
+
+    if let Some(mem_cache) = mem_cache {
+        if let Some((quote, age)) = mem_cache.read(symbol, ttl) {
+            if verbose {
+                eprintln!("{symbol}: in-memory cache hit ({age:?} old)");
+            }
+            return Ok((quote, age, false));
+        }
+    }
+
+    if let Some((quote, age)) = cache::read(config.cache_dir.as_deref(), symbol, ttl) {
+        if verbose {
+            eprintln!("{symbol}: cache hit ({age:?} old)");
+        }
+        if let Some(mem_cache) = mem_cache {
+            mem_cache.write(
+                &quote,
+                cache::read_etag(config.cache_dir.as_deref(), symbol).as_deref(),
+            );
+        }
+        return Ok((quote, age, false));
+    }
+
+    let stale = mem_cache
+        .and_then(|mem_cache| mem_cache.read_stale(symbol))
+        .or_else(|| cache::read(config.cache_dir.as_deref(), symbol, Duration::MAX));
+    if let Some((quote, age)) = stale {
+        if verbose {
+            eprintln!("{symbol}: serving stale cache ({age:?} old), refreshing in background");
+        }
+        spawn_background_refresh(
+            config,
+            provider_override,
+            symbol.to_string(),
+            mem_cache.cloned(),
+        );
+        return Ok((quote, age, true));
+    }
+
+    let conditional = match provider_override {
+        Some(name) => fetch_conditional(name, config.api_key.as_deref(), symbol, None)?,
+        None => fetch_conditional_with_fallback(
+            &config.provider_chain(),
+            config.api_key.as_deref(),
+            symbol,
+            None,
+        )?,
+    };
+    // A conditional fetch with no etag to send never comes back 304.
+    let provider::ConditionalQuote::Fresh(quote, etag) = conditional else {
+        anyhow::bail!("provider returned \"not modified\" for a first-ever fetch of {symbol}");
+    };
+    match mem_cache {
+        Some(mem_cache) => mem_cache.write(&quote, etag.as_deref()),
+        None => cache::write(config.cache_dir.as_deref(), &quote, etag.as_deref())?,
+    }
+    Ok((*quote, Duration::ZERO, false))
+}
+
+/// Fetches `symbol` on a background thread and caches the result, letting
+/// `fetch_ticker` return a stale quote immediately instead of blocking on
+/// this. Sends the already-cached ETag (if any) as a conditional GET, so a
+/// quote that hasn't moved since the last check-in -- common after hours --
+/// comes back as a cheap HTTP 304 instead of a full response; `cache::touch`
+/// then just re-stamps the existing quote as fresh. Errors are dropped —
+/// the next stale read (or the daemon's next rotation tick) just tries
+/// again.
+fn spawn_background_refresh(
+    config: &Config,
+    provider_override: Option<&str>,
+    symbol: String,
+    mem_cache: Option<Arc<cache::MemCache>>,
+) {
+    let cache_dir = config.cache_dir.clone();
+    let api_key = config.api_key.clone();
+    let provider_override = provider_override.map(str::to_string);
+    let providers: Vec<String> = config
+        .provider_chain()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    thread::spawn(move || {
+        let etag = mem_cache
+            .as_deref()
+            .and_then(|mem_cache| mem_cache.read_etag(&symbol))
+            .or_else(|| cache::read_etag(cache_dir.as_deref(), &symbol));
+        let result = match &provider_override {
+            Some(name) => fetch_conditional(name, api_key.as_deref(), &symbol, etag.as_deref()),
+            None => {
+                let chain: Vec<&str> = providers.iter().map(String::as_str).collect();
+                fetch_conditional_with_fallback(
+                    &chain,
+                    api_key.as_deref(),
+                    &symbol,
+                    etag.as_deref(),
+                )
+            }
+        };
+        match result {
+            Ok(provider::ConditionalQuote::Fresh(quote, new_etag)) => match &mem_cache {
+                Some(mem_cache) => mem_cache.write(&quote, new_etag.as_deref()),
+                None => {
+                    let _ = cache::write(cache_dir.as_deref(), &quote, new_etag.as_deref());
+                }
+            },
+            Ok(provider::ConditionalQuote::NotModified) => match &mem_cache {
+                Some(mem_cache) => mem_cache.touch(&symbol),
+                None => {
+                    let _ = cache::touch(cache_dir.as_deref(), &symbol);
+                }
+            },
+            Err(_) => {}
+        }
+    });
+}
+
+/// How often `stocker daemon`'s in-memory cache is written to disk. A crash
+/// or `kill -9` between flushes loses at most this much cached history --
+/// an acceptable trade since the daemon just re-fetches on its next tick
+/// either way.
+const MEM_CACHE_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Starts a background thread that periodically persists `mem_cache`'s dirty
+/// entries to the on-disk cache, so `stocker daemon`'s rotation loop itself
+/// never blocks on a database write.
+fn spawn_mem_cache_flush(config: Arc<Config>, mem_cache: Arc<cache::MemCache>) {
+    thread::spawn(move || loop {
+        thread::sleep(MEM_CACHE_FLUSH_INTERVAL);
+        let _ = mem_cache.flush(config.cache_dir.as_deref());
+    });
+}
+
+/// Best-effort cache warm-up for the whole active watchlist: tickers that
+/// don't need a cache refresh yet, have a per-ticker provider override, or
+/// are routed by a `ticker::resolve` prefix (crypto, fx, ...) are skipped,
+/// since there's no shared provider to batch them with. The rest share the
+/// default provider chain's first provider, so if it supports batching (see
+/// [`provider::QuoteProvider::fetch_quotes`]) they're all fetched and cached
+/// in one request instead of one per ticker as the caller rotates through
+/// them. Failures here are silently ignored -- `fetch_ticker` falls back to
+/// fetching individually for whatever didn't get primed.
+fn prime_cache(config: &Config, watchlist: Option<&str>, verbose: bool) {
+    let chain = config.provider_chain();
+    let Some(&provider_name) = chain.first() else {
+        return;
+    };
+    let now = SystemTime::now();
+    let tickers = match config.active_tickers(watchlist) {
+        Ok(tickers) => tickers,
+        Err(_) => return,
+    };
+    let symbols: Vec<&str> = tickers
+        .iter()
+        .filter(|entry| entry.provider().is_none())
+        .filter_map(|entry| {
+            let (prefix_override, symbol) = ticker::resolve(entry.symbol());
+            if prefix_override.is_some() {
+                return None;
+            }
+            let ttl = config.cache_ttl_for(
+                ticker::is_daily_only(entry.symbol()),
+                entry.cache_seconds(),
+                entry.market_hours(),
+                entry.weekend_days(),
+                now,
+            );
+            if cache::read(config.cache_dir.as_deref(), symbol, ttl).is_some() {
+                return None;
+            }
+            Some(symbol)
+        })
+        .collect();
+    if symbols.len() < 2 {
+        return;
+    }
+
+    let provider = match provider::from_name(provider_name, config.api_key.as_deref()) {
+        Ok(provider) => provider,
+        Err(err) => {
+            if verbose {
+                eprintln!("stocker: skipping batch cache priming: {err:#}");
+            }
+            return;
+        }
+    };
+    match provider.fetch_quotes(&symbols) {
+        Ok(quotes) => {
+            let count = quotes.len();
+            for quote in quotes {
+                let _ = cache::write(config.cache_dir.as_deref(), &quote, None);
+            }
+            if verbose {
+                eprintln!("primed cache for {count} ticker(s) via {provider_name} in one request");
+            }
+        }
+        Err(err) => {
+            if verbose {
+                eprintln!(
+                    "stocker: batch cache priming via {provider_name} failed, \
+                     falling back to per-ticker fetches: {err:#}"
+                );
+            }
+        }
+    }
+}
+
+/// Fetches every ticker's quote in the active watchlist, discarding
+/// cache-age and staleness information the TUI has no use for.
+fn fetch_all(config: &Config, watchlist: Option<&str>, verbose: bool) -> Result<Vec<Quote>> {
+    prime_cache(config, watchlist, verbose);
+    config
+        .active_tickers(watchlist)?
+        .iter()
+        .map(|entry| fetch_ticker(config, entry, verbose, None).map(|(quote, ..)| quote))
+        .collect()
+}
+
+/// Drives a streaming output format: print the protocol header once, then
+/// loop forever printing one line of updated blocks every `rotation_seconds`
+/// (see `Config::rotation_interval`).
+fn run_streaming(
+    format: Format,
+    config: &Config,
+    watchlist: Option<&str>,
+    verbose: bool,
+) -> Result<()> {
+    match format {
+        Format::I3bar => {
+            let tickers = config.active_tickers(watchlist)?;
+            println!("{}", output::i3bar_header());
+            println!("[");
+            loop {
+                prime_cache(config, watchlist, verbose);
+                let mut quotes = Vec::with_capacity(tickers.len());
+                for entry in tickers {
+                    let (quote, ..) = fetch_ticker(config, entry, verbose, None)?;
+                    let quote = apply_unit_divisor(quote, entry);
+                    let (quote, _) =
+                        convert_to_base(config, quote, entry.currency().unwrap_or("USD"), verbose);
+                    quotes.push(quote);
+                }
+                println!("{},", output::i3bar_render_blocks(&quotes));
+                thread::sleep(config.rotation_interval());
+            }
+        }
+        _ => anyhow::bail!("{format:?} is not a streaming format"),
+    }
+}
+
+fn fetch(provider_name: &str, api_key: Option<&str>, symbol: &str) -> Result<Quote> {
+    provider::from_name(provider_name, api_key)?.fetch_quote(symbol)
+}
+
+/// Tries each provider in `chain` in order, returning the first successful
+/// quote. The whole chain failing is reported via the last provider's error.
+fn fetch_with_fallback(chain: &[&str], api_key: Option<&str>, symbol: &str) -> Result<Quote> {
+    let mut last_err = None;
+    for provider_name in chain {
+        match fetch(provider_name, api_key, symbol) {
+            Ok(quote) => return Ok(quote),
+            Err(err) => last_err = Some(err),
+        }
+    }
+    match last_err {
+        Some(err) => Err(err)
+            .with_context(|| format!("all providers in the fallback chain failed for {symbol}")),
+        None => anyhow::bail!("no providers configured"),
+    }
+}
+
+fn fetch_conditional(
+    provider_name: &str,
+    api_key: Option<&str>,
+    symbol: &str,
+    etag: Option<&str>,
+) -> Result<provider::ConditionalQuote> {
+    provider::from_name(provider_name, api_key)?.fetch_quote_conditional(symbol, etag)
+}
+
+/// Like `fetch_with_fallback`, but conditional: `etag` (if any) is sent to
+/// whichever provider in `chain` ends up serving the request. Falling
+/// through to a later provider after an earlier one errors is rare enough
+/// in practice that reusing the same etag across providers -- rather than
+/// tracking one per provider -- is a reasonable simplification.
+fn fetch_conditional_with_fallback(
+    chain: &[&str],
+    api_key: Option<&str>,
+    symbol: &str,
+    etag: Option<&str>,
+) -> Result<provider::ConditionalQuote> {
+    let mut last_err = None;
+    for provider_name in chain {
+        match fetch_conditional(provider_name, api_key, symbol, etag) {
+            Ok(result) => return Ok(result),
+            Err(err) => last_err = Some(err),
+        }
+    }
+    match last_err {
+        Some(err) => Err(err)
+            .with_context(|| format!("all providers in the fallback chain failed for {symbol}")),
+        None => anyhow::bail!("no providers configured"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_unit_divisor, scale_quote_prices};
+    use crate::config::TickerEntry;
+    use crate::quote::Quote;
+
+    fn quote() -> Quote {
+        Quote {
+            ticker: "VWRL.L".into(),
+            last: 10_000.0,
+            prev_close: 9_900.0,
+            after_hours: Some(10_050.0),
+            open: Some(9_950.0),
+            volume: None,
+            day_high: Some(10_100.0),
+            day_low: Some(9_900.0),
+            bid: Some(9_995.0),
+            ask: Some(10_005.0),
+            last_trade_time: None,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn scale_quote_prices_scales_every_price_field() {
+        let scaled = scale_quote_prices(quote(), 2.0);
+        assert_eq!(scaled.last, 20_000.0);
+        assert_eq!(scaled.prev_close, 19_800.0);
+        assert_eq!(scaled.after_hours, Some(20_100.0));
+        assert_eq!(scaled.open, Some(19_900.0));
+        assert_eq!(scaled.day_high, Some(20_200.0));
+        assert_eq!(scaled.day_low, Some(19_800.0));
+        assert_eq!(scaled.bid, Some(19_990.0));
+        assert_eq!(scaled.ask, Some(20_010.0));
+    }
+
+    #[test]
+    fn apply_unit_divisor_scales_bid_ask_and_day_range() {
+        let entry: TickerEntry = toml::from_str(
+            r#"
+            symbol = "VWRL.L"
+            unit_divisor = 100.0
+            "#,
+        )
+        .unwrap();
+        let divided = apply_unit_divisor(quote(), &entry);
+        assert_eq!(divided.last, 100.0);
+        assert_eq!(divided.day_high, Some(101.0));
+        assert_eq!(divided.day_low, Some(99.0));
+        assert_eq!(divided.bid, Some(99.95));
+        assert_eq!(divided.ask, Some(100.05));
+    }
+
+    #[test]
+    fn apply_unit_divisor_is_a_no_op_without_one_configured() {
+        let entry = TickerEntry::Plain("AAPL".into());
+        assert_eq!(apply_unit_divisor(quote(), &entry), quote());
+    }
+}
@@ -0,0 +1,48 @@
+//! Compacts large magnitudes (volume, market cap) into `1.2M` / `3.4B`
+//! style strings, so a tooltip line stays readable instead of spelling out
+//! every digit.
+
+/// `value` formatted with a `K`/`M`/`B`/`T` suffix once its magnitude is at
+/// least `threshold` (see `Config::compact_number_threshold`), else the bare
+/// number with one decimal place. Negative values compact the same way,
+/// keeping their sign.
+pub fn compact(value: f64, threshold: f64) -> String {
+    let sign = if value < 0.0 { "-" } else { "" };
+    let magnitude = value.abs();
+    if magnitude < threshold {
+        return format!("{value:.1}");
+    }
+    const UNITS: [(f64, &str); 4] = [
+        (1_000_000_000_000.0, "T"),
+        (1_000_000_000.0, "B"),
+        (1_000_000.0, "M"),
+        (1_000.0, "K"),
+    ];
+    for (scale, suffix) in UNITS {
+        if magnitude >= scale {
+            return format!("{sign}{:.1}{suffix}", magnitude / scale);
+        }
+    }
+    format!("{value:.1}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compacts_millions_and_billions() {
+        assert_eq!(compact(1_234_567.0, 1_000.0), "1.2M");
+        assert_eq!(compact(3_400_000_000.0, 1_000.0), "3.4B");
+    }
+
+    #[test]
+    fn leaves_values_below_the_threshold_uncompacted() {
+        assert_eq!(compact(999.0, 1_000.0), "999.0");
+    }
+
+    #[test]
+    fn keeps_the_sign_on_negative_values() {
+        assert_eq!(compact(-2_500_000.0, 1_000.0), "-2.5M");
+    }
+}
@@ -0,0 +1,37 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::quote::Quote;
+
+use super::Options;
+
+/// Unix timestamp (seconds) for the `timestamp` column, since the quote
+/// itself doesn't carry a fetch time.
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// `timestamp,ticker,last,prev_close,pct_change,class`, suitable for
+/// appending to a log file from a cron job.
+pub fn render(quote: &Quote, options: &Options) -> String {
+    let prec = options.precision;
+    let pct = options.pct(quote);
+    let class = super::class_with_hysteresis(
+        pct,
+        options.thresholds,
+        options.paused,
+        options.stale,
+        options.previous_class.as_deref(),
+    );
+    format!(
+        "{},{},{:.prec$},{:.prec$},{:.prec$},{}",
+        now_unix(),
+        options.label(quote),
+        quote.last,
+        quote.prev_close,
+        pct,
+        super::display_class(class, options.class_names)
+    )
+}
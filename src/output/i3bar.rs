@@ -0,0 +1,50 @@
+use serde::Serialize;
+
+use crate::config::Thresholds;
+use crate::quote::Quote;
+
+/// One block in the i3bar/swaybar protocol's per-update array.
+#[derive(Serialize)]
+struct Block {
+    full_text: String,
+    short_text: String,
+    color: Option<String>,
+    name: String,
+}
+
+fn color_for(class: &str) -> Option<String> {
+    match class {
+        "wayup" | "up" => Some("#00ff00".to_string()),
+        "critdown" | "down" => Some("#ff0000".to_string()),
+        _ => None,
+    }
+}
+
+/// Streaming formats update every ticker in one call, so there's no single
+/// per-ticker `Options` to draw from; classification uses the default
+/// thresholds until per-ticker overrides are threaded into streaming mode.
+fn block(quote: &Quote) -> Block {
+    let pct = quote.percent_change();
+    Block {
+        full_text: format!("{} {:.2} ({:+.2}%)", quote.ticker, quote.last, pct),
+        short_text: format!("{} {:+.2}%", quote.ticker, pct),
+        color: color_for(super::class(pct, Thresholds::default(), false, false)),
+        name: quote.ticker.clone(),
+    }
+}
+
+/// The one-time header i3bar/swaybar expects before the infinite block
+/// array stream begins. `click_events` isn't advertised here: unlike
+/// `stocker daemon`, which reads them via `click::watch`, this streaming
+/// path (`run_streaming`) never reads stdin at all, so claiming to support
+/// them would just make swaybar send JSON nobody's listening for.
+pub fn header() -> String {
+    r#"{"version":1,"click_events":false}"#.to_string()
+}
+
+/// One line of the infinite JSON array: `[block, block, ...]`, comma
+/// separated between updates as the protocol requires.
+pub fn render_blocks(quotes: &[Quote]) -> String {
+    let blocks: Vec<Block> = quotes.iter().map(block).collect();
+    serde_json::to_string(&blocks).expect("i3bar blocks are always serializable")
+}
@@ -0,0 +1,477 @@
+mod csv;
+mod i3bar;
+mod lemonbar;
+mod plain;
+mod polybar;
+mod prometheus;
+mod template;
+mod waybar;
+mod xmobar;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::config::{MarketStatus, Thresholds};
+use crate::locale::NumberFormat;
+use crate::quote::Quote;
+
+pub use i3bar::{header as i3bar_header, render_blocks as i3bar_render_blocks};
+
+/// Which bar/tool the rendered output is meant for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Waybar,
+    Polybar,
+    Xmobar,
+    Plain,
+    Lemonbar,
+    I3bar,
+    Csv,
+    Prometheus,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> anyhow::Result<Self> {
+        match name {
+            "waybar" => Ok(Format::Waybar),
+            "polybar" => Ok(Format::Polybar),
+            "xmobar" => Ok(Format::Xmobar),
+            "plain" => Ok(Format::Plain),
+            "lemonbar" | "dzen2" => Ok(Format::Lemonbar),
+            "i3bar" | "swaybar" => Ok(Format::I3bar),
+            "csv" => Ok(Format::Csv),
+            "prometheus" => Ok(Format::Prometheus),
+            other => anyhow::bail!("unknown output format: {other}"),
+        }
+    }
+
+    /// Whether this format speaks a streaming protocol (a header followed by
+    /// an infinite array of updates) rather than printing one line per run.
+    pub fn is_streaming(self) -> bool {
+        matches!(self, Format::I3bar)
+    }
+}
+
+/// Per-format rendering knobs sourced from `Config` and, where a ticker
+/// entry sets its own, from that entry. Most formats ignore most of these;
+/// each formatter picks out what applies to it.
+#[derive(Debug, Clone)]
+pub struct Options<'a> {
+    /// Emit Pango markup in waybar's `text` field instead of plain text.
+    pub pango: bool,
+    /// Wrap plain-text output in ANSI color escapes.
+    pub color: bool,
+    /// Overrides waybar's `text` field, e.g. `"{ticker} {last} ({pct}%)"`.
+    pub text_template: Option<&'a str>,
+    /// Overrides waybar's `tooltip` field.
+    pub tooltip_template: Option<&'a str>,
+    /// Percent-change boundaries used to classify the quote.
+    pub thresholds: Thresholds,
+    /// Decimal places shown for `last`/`prev_close`.
+    pub precision: usize,
+    /// Overrides the ticker symbol shown in output, e.g. `S&P500` for `^GSPC`.
+    pub display_name: Option<&'a str>,
+    /// Whether the daemon's rotation is currently paused (see `stocker ctl
+    /// pause`), e.g. for screen sharing. Overrides the usual threshold class
+    /// with `"paused"`.
+    pub paused: bool,
+    /// Whether this quote is a stale cache hit served while a fresh one is
+    /// fetched in the background (see `fetch_ticker`'s stale-while-revalidate
+    /// strategy). Overrides the usual threshold class with `"stale"`, unless
+    /// `paused` already overrides it.
+    pub stale: bool,
+    /// The ticker's exchange session and how long until it next changes, if
+    /// `market_hours` is configured for it (see `Config::market_status_for`).
+    /// Unlike `paused`/`stale`, this doesn't override the threshold class --
+    /// formatters that support it (currently just waybar) add it alongside,
+    /// as an extra CSS class and tooltip line.
+    pub market_status: Option<(MarketStatus, Duration)>,
+    /// Mirrors `Config::show_after_hours`: display a provider's
+    /// extended-hours print (see `Quote::after_hours`) instead of the
+    /// regular session's close once one is available. Like
+    /// `market_status`, this is additive rather than an override -- a
+    /// quote with no extended-hours print just renders as it always has,
+    /// and only waybar currently acts on it.
+    pub show_after_hours: bool,
+    /// The price to compute percent change against, resolved by
+    /// `Config::baseline_price` from `percent_change_baseline` and a ticker's
+    /// own `baseline`/`reference_price`. `None` means "use `quote.prev_close`",
+    /// the same as before this setting existed.
+    pub percent_change_baseline: Option<f64>,
+    /// This ticker's currency symbol and whether it goes after the amount
+    /// (see `crate::currency::symbol`), resolved from a ticker's own
+    /// `currency` or the `"USD"` default. Only `plain` and `waybar`
+    /// currently render it; the others never showed a currency symbol to
+    /// begin with.
+    pub currency: (&'a str, bool),
+    /// A pre-rendered `▂▃▅▇▆`-style sparkline of recent cached prices (see
+    /// `cache::history_since` and the `sparkline` module), if
+    /// `show_sparkline` is on and enough history was on hand to draw one.
+    /// Owned rather than borrowed like the other optional strings here,
+    /// since it's built fresh from cache data each run rather than sourced
+    /// from `Config`. Like `market_status`, only waybar currently draws it.
+    pub sparkline: Option<String>,
+    /// The `[glyphs]` table (`Config::glyphs`), mapping a threshold class to
+    /// a glyph prepended to the text, e.g. `"up" -> "▲"`; see `Options::glyph`.
+    /// A class with no entry here just renders without one.
+    pub glyphs: Option<&'a HashMap<String, String>>,
+    /// This ticker's own `icon` (`TickerEntry::Detailed::icon`), shown
+    /// alongside its symbol rather than in place of it. See `Options::labeled`.
+    pub icon: Option<&'a str>,
+    /// Mirrors `Config::show_absolute_change`: include the absolute change
+    /// (see `Options::change`) alongside the percentage in the default
+    /// text. A custom `text_template`/`tooltip_template` can reference
+    /// `{change}` regardless of this flag.
+    pub show_absolute_change: bool,
+    /// Mirrors `Config::compact_threshold`: the magnitude a number (volume)
+    /// must reach before `RenderContext` shows it compacted, e.g. `1.2M`
+    /// instead of `1200000.0`. See `crate::numfmt::compact`.
+    pub compact_number_threshold: f64,
+    /// This ticker's `(low, high)` 52-week range, resolved fresh each run
+    /// from a synthetic `52w:<symbol>` lookup (see `crate::ticker::resolve`)
+    /// if `show_52_week_range` is on and the lookup succeeded. Like
+    /// `sparkline`, only waybar currently draws it.
+    pub week52_range: Option<(f64, f64)>,
+    /// Mirrors `Config::show_spread`: include the bid/ask spread alongside
+    /// the price in the default text, when `quote.bid`/`quote.ask` are both
+    /// present. The tooltip always shows the spread when available,
+    /// regardless of this flag.
+    pub show_spread: bool,
+    /// A pre-rendered `"<label>: <pct>%"` line per active ticker, if
+    /// `show_watchlist_tooltip` is on and the cache had something for at
+    /// least one of them -- see `crate::cache::read`. Owned rather than
+    /// borrowed for the same reason `sparkline` is. Only waybar draws it.
+    pub watchlist_summary: Option<String>,
+    /// The decimal/thousands separators used by `price` and `signed`, e.g.
+    /// `1.234,56` instead of `1,234.56` for a German locale -- see
+    /// `Config::locale` and `crate::locale::resolve`.
+    pub number_format: NumberFormat,
+    /// Mirrors `Config::show_color_gradient`: color the pango span (and the
+    /// JSON `color` field) on a continuous red->grey->green scale (see
+    /// `gradient_color`) instead of the four discrete threshold colors, so
+    /// small and large moves are visually distinct. Only waybar draws it;
+    /// `paused`/`stale`/`afterhours` still get their own fixed color.
+    pub color_gradient: bool,
+    /// The `[class_names]` table (`Config::class_names`), renaming an
+    /// emitted class before it reaches waybar's `class`/`alt` fields or the
+    /// CSV `class` column. See `display_class`.
+    pub class_names: Option<&'a HashMap<String, String>>,
+    /// Mirrors `Config::text_width`: pads waybar's default text with
+    /// trailing spaces to at least this many characters, so the module's
+    /// footprint stays constant as it rotates between tickers. `None` means
+    /// no padding.
+    pub text_width: Option<usize>,
+    /// This ticker's market cap, resolved fresh each run from a synthetic
+    /// `mcap:<symbol>` lookup (see `crate::ticker::resolve`) if
+    /// `show_market_cap` is on and the lookup succeeded. Like
+    /// `week52_range`, only waybar currently draws it.
+    pub market_cap: Option<f64>,
+    /// The threshold class this ticker was last rendered as, if the caller
+    /// looked one up -- fed to `class_with_hysteresis` so a ticker hovering
+    /// at a boundary doesn't flip classes every refresh. Owned rather than
+    /// borrowed since it comes from a cache read, not `Config`.
+    pub previous_class: Option<String>,
+}
+
+impl Default for Options<'_> {
+    fn default() -> Self {
+        Options {
+            pango: false,
+            color: false,
+            text_template: None,
+            tooltip_template: None,
+            thresholds: Thresholds::default(),
+            precision: 2,
+            display_name: None,
+            paused: false,
+            stale: false,
+            market_status: None,
+            show_after_hours: false,
+            percent_change_baseline: None,
+            currency: ("$", false),
+            sparkline: None,
+            glyphs: None,
+            icon: None,
+            show_absolute_change: false,
+            compact_number_threshold: 1_000.0,
+            week52_range: None,
+            show_spread: false,
+            watchlist_summary: None,
+            number_format: NumberFormat::default(),
+            color_gradient: false,
+            class_names: None,
+            text_width: None,
+            market_cap: None,
+            previous_class: None,
+        }
+    }
+}
+
+impl<'a> Options<'a> {
+    /// The label to show for the quote: `display_name` if the ticker entry
+    /// set one, else the raw ticker symbol.
+    pub fn label(&self, quote: &'a Quote) -> &'a str {
+        self.display_name.unwrap_or(&quote.ticker)
+    }
+
+    /// `label`, with this ticker's `icon` (if any) shown alongside it, e.g.
+    /// ` AAPL`.
+    pub fn labeled(&self, quote: &'a Quote) -> String {
+        match self.icon {
+            Some(icon) => format!("{icon} {}", self.label(quote)),
+            None => self.label(quote).to_string(),
+        }
+    }
+
+    /// `quote`'s percent change against `percent_change_baseline`, or
+    /// `quote.percent_change()` (i.e. against `prev_close`) if this format
+    /// doesn't have one resolved for it.
+    pub fn pct(&self, quote: &Quote) -> f64 {
+        match self.percent_change_baseline {
+            Some(baseline) => quote.percent_change_against(baseline),
+            None => quote.percent_change(),
+        }
+    }
+
+    /// `value` formatted to `precision` places with `currency`'s symbol in
+    /// the right spot, e.g. `$123.45` or `123,45 €`, using `number_format`'s
+    /// decimal/thousands separators.
+    pub fn price(&self, value: f64) -> String {
+        let (symbol, suffix) = self.currency;
+        let formatted = self.number_format.format(value, self.precision, false);
+        if suffix {
+            format!("{formatted} {symbol}")
+        } else {
+            format!("{symbol}{formatted}")
+        }
+    }
+
+    /// `value` formatted to `precision` places with an explicit `+`/`-`
+    /// sign and `number_format`'s separators, e.g. `+1,23%`'s `+1,23` --
+    /// the sign-carrying counterpart to `price` used for changes and
+    /// percentages, which are never prefixed with a currency symbol.
+    pub fn signed(&self, value: f64) -> String {
+        self.number_format.format(value, self.precision, true)
+    }
+
+    /// The configured glyph for `class`, if `[glyphs]` set one.
+    pub fn glyph(&self, class: &str) -> Option<&str> {
+        self.glyphs?.get(class).map(String::as_str)
+    }
+
+    /// `quote`'s absolute change against `percent_change_baseline` (or
+    /// `quote.prev_close`), e.g. `+1.23` -- the same move `pct` expresses as
+    /// a percentage.
+    pub fn change(&self, quote: &Quote) -> f64 {
+        quote.last - self.percent_change_baseline.unwrap_or(quote.prev_close)
+    }
+}
+
+/// The threshold classification shared across every formatter: how far the
+/// quote's percent change falls outside `thresholds`, or `"paused"`/`"stale"`
+/// if the daemon's rotation is paused or this quote is a stale
+/// stale-while-revalidate hit, either of which overrides the usual
+/// classification (`paused` wins if somehow both apply).
+pub fn class(pct: f64, thresholds: Thresholds, paused: bool, stale: bool) -> &'static str {
+    if paused {
+        return "paused";
+    }
+    if stale {
+        return "stale";
+    }
+    if pct.abs() <= thresholds.flat {
+        "flat"
+    } else if pct < thresholds.critdown {
+        "critdown"
+    } else if pct < thresholds.down {
+        "down"
+    } else if pct > thresholds.wayup {
+        "wayup"
+    } else if pct > 0.0 {
+        "up"
+    } else {
+        "flat"
+    }
+}
+
+/// `class`, but sticky around `previous` (the class this same ticker was
+/// last classified as, if known): once classified, a move must clear the
+/// boundary it crossed by more than `thresholds.hysteresis` percentage
+/// points before it's actually reclassified, so a ticker hovering right at a
+/// threshold doesn't flip the class every refresh. `thresholds.hysteresis`
+/// of 0.0 (the default) disables this entirely and just returns `class`'s
+/// naive result. `paused`/`stale` always take effect immediately in either
+/// direction -- there's no point damping an operator-visible state change.
+pub fn class_with_hysteresis(
+    pct: f64,
+    thresholds: Thresholds,
+    paused: bool,
+    stale: bool,
+    previous: Option<&str>,
+) -> &'static str {
+    let naive = class(pct, thresholds, paused, stale);
+    if paused || stale || thresholds.hysteresis <= 0.0 {
+        return naive;
+    }
+    let Some(previous) = previous else {
+        return naive;
+    };
+    if naive == previous {
+        return naive;
+    }
+    // Map to a canonical `&'static str` rather than trusting the caller's
+    // borrowed `previous` -- also naturally excludes a stored "paused" or
+    // "stale" from sticking now that this ticker is neither.
+    let previous = match previous {
+        "critdown" => "critdown",
+        "down" => "down",
+        "flat" => "flat",
+        "up" => "up",
+        "wayup" => "wayup",
+        _ => return naive,
+    };
+    // `previous` still applies if nudging `pct` by the hysteresis margin in
+    // either direction would still land in its territory -- i.e. the move
+    // hasn't cleared the boundary by more than that margin yet.
+    let sticks = class(pct - thresholds.hysteresis, thresholds, false, false) == previous
+        || class(pct + thresholds.hysteresis, thresholds, false, false) == previous;
+    if sticks {
+        previous
+    } else {
+        naive
+    }
+}
+
+/// Whether `pct` clears `thresholds.critical`, the optional extra tier past
+/// `critdown` for moves severe enough to warrant blinking/urgent CSS. Kept
+/// separate from `class` since it's an additional flag layered on top of the
+/// usual classification, not a replacement for it -- see
+/// `waybar::render`'s `"critical"` class.
+pub fn is_critical(pct: f64, thresholds: Thresholds) -> bool {
+    thresholds.critical.is_some_and(|critical| pct < critical)
+}
+
+/// `class`, renamed per `[class_names]` if it has an entry for it -- the
+/// only place a canonical class name should turn into a user-facing one;
+/// everything else (glyph lookup, the color gradient, threshold
+/// classification) keys off the canonical name from `class` directly.
+pub fn display_class<'a>(
+    class: &'a str,
+    class_names: Option<&'a HashMap<String, String>>,
+) -> &'a str {
+    class_names
+        .and_then(|names| names.get(class))
+        .map(String::as_str)
+        .unwrap_or(class)
+}
+
+/// A red->grey->green hex color proportional to `pct`, instead of the four
+/// discrete threshold classes: grey at 0%, saturating to red by
+/// `thresholds.critdown` (and beyond) or green by `thresholds.wayup` (and
+/// beyond). Used by waybar's gradient mode (`show_color_gradient`) so a
+/// -0.5% dip and a -6% drop are visually distinct rather than both just
+/// rendering "down".
+pub fn gradient_color(pct: f64, thresholds: Thresholds) -> String {
+    const GREY: (f64, f64, f64) = (136.0, 136.0, 136.0);
+    const RED: (f64, f64, f64) = (255.0, 0.0, 0.0);
+    const GREEN: (f64, f64, f64) = (0.0, 255.0, 0.0);
+    let (target, t) = if pct >= 0.0 {
+        (GREEN, (pct / thresholds.wayup).clamp(0.0, 1.0))
+    } else {
+        (RED, (pct / thresholds.critdown).clamp(0.0, 1.0))
+    };
+    let lerp = |from: f64, to: f64| (from + (to - from) * t).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        lerp(GREY.0, target.0),
+        lerp(GREY.1, target.1),
+        lerp(GREY.2, target.2)
+    )
+}
+
+/// Renders an arbitrary user-supplied template (e.g. `click_url_template`)
+/// against a quote, using the same `{ticker}`/`{last}`/`{pct}` placeholders
+/// as `text_template`.
+pub fn render_template(template: &str, quote: &Quote) -> anyhow::Result<String> {
+    self::template::render(
+        template,
+        // No `Options` in scope here, so fall back to the same default
+        // `compact_number_threshold` a `Config` without one set would use.
+        &self::template::RenderContext::new(quote, None, None, 1_000.0),
+    )
+}
+
+pub fn render(
+    format: Format,
+    quote: &Quote,
+    options: &Options,
+    cache_age: Duration,
+) -> anyhow::Result<String> {
+    Ok(match format {
+        Format::Waybar => waybar::render(quote, options, cache_age)?,
+        Format::Polybar => polybar::render(quote, options),
+        Format::Xmobar => xmobar::render(quote, options),
+        Format::Plain => plain::render(quote, options),
+        Format::Lemonbar => lemonbar::render(quote, options),
+        Format::I3bar => anyhow::bail!("i3bar format is streaming-only; use render_blocks"),
+        Format::Csv => csv::render(quote, options),
+        Format::Prometheus => prometheus::render(quote, options),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thresholds_with_hysteresis(hysteresis: f64) -> Thresholds {
+        Thresholds {
+            hysteresis,
+            ..Thresholds::default()
+        }
+    }
+
+    #[test]
+    fn hysteresis_zero_behaves_like_the_naive_class() {
+        let thresholds = thresholds_with_hysteresis(0.0);
+        // -3.5% is naively "critdown"; without hysteresis, "down" doesn't stick.
+        assert_eq!(
+            class_with_hysteresis(-3.5, thresholds, false, false, Some("down")),
+            "critdown"
+        );
+    }
+
+    #[test]
+    fn a_move_still_inside_the_hysteresis_band_sticks_to_previous() {
+        let thresholds = thresholds_with_hysteresis(1.0);
+        // -3.5% naively reclassifies as "critdown", but nudging by the 1.0
+        // margin (-2.5%) still lands in "down"'s territory, so it sticks.
+        assert_eq!(
+            class_with_hysteresis(-3.5, thresholds, false, false, Some("down")),
+            "down"
+        );
+    }
+
+    #[test]
+    fn a_move_past_the_hysteresis_band_reclassifies() {
+        let thresholds = thresholds_with_hysteresis(1.0);
+        // -4.5% nudged back toward "down" by the 1.0 margin is still -3.5%,
+        // which is past the -3.0 critdown boundary, so the band is cleared.
+        assert_eq!(
+            class_with_hysteresis(-4.5, thresholds, false, false, Some("down")),
+            "critdown"
+        );
+    }
+
+    #[test]
+    fn paused_and_stale_always_take_effect_immediately() {
+        let thresholds = thresholds_with_hysteresis(5.0);
+        assert_eq!(
+            class_with_hysteresis(-3.5, thresholds, true, false, Some("down")),
+            "paused"
+        );
+        assert_eq!(
+            class_with_hysteresis(-3.5, thresholds, false, true, Some("down")),
+            "stale"
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use crate::quote::Quote;
+
+use super::Options;
+
+fn ansi_color(class: &str) -> &'static str {
+    match class {
+        "wayup" | "up" => "\x1b[32m",
+        "critdown" | "down" => "\x1b[31m",
+        _ => "",
+    }
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Bare `AAPL $123.45 (+0.67%)` text for tmux status lines and shell
+/// prompts that don't want to parse JSON. The currency symbol comes from
+/// `options.currency`, e.g. `AAPL $123.45` but `SAP.DE 123,45 €`. A
+/// `[glyphs]` entry for the quote's threshold class, if configured, is
+/// prepended, e.g. `▲ AAPL $123.45 (+0.67%)`.
+pub fn render(quote: &Quote, options: &Options) -> String {
+    let pct = options.pct(quote);
+    let price = options.price(quote.last);
+    let class = super::class_with_hysteresis(
+        pct,
+        options.thresholds,
+        options.paused,
+        options.stale,
+        options.previous_class.as_deref(),
+    );
+    let glyph = match options.glyph(class) {
+        Some(glyph) => format!("{glyph} "),
+        None => String::new(),
+    };
+    let body = if options.show_absolute_change {
+        format!(
+            "{glyph}{} {price} ({}, {}%)",
+            options.labeled(quote),
+            options.signed(options.change(quote)),
+            options.signed(pct)
+        )
+    } else {
+        format!(
+            "{glyph}{} {price} ({}%)",
+            options.labeled(quote),
+            options.signed(pct)
+        )
+    };
+    if !options.color {
+        return body;
+    }
+    let color_code = ansi_color(class);
+    if color_code.is_empty() {
+        body
+    } else {
+        format!("{color_code}{body}{ANSI_RESET}")
+    }
+}
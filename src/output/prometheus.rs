@@ -0,0 +1,16 @@
+use crate::quote::Quote;
+
+use super::Options;
+
+/// Textfile-collector-friendly gauges for `stocker_last_price` and
+/// `stocker_change_pct`, one ticker's worth per call. `render` is called
+/// once per ticker in `main`, so callers append output across tickers into
+/// the same `.prom` file.
+pub fn render(quote: &Quote, options: &Options) -> String {
+    format!(
+        "stocker_last_price{{ticker=\"{ticker}\"}} {last}\nstocker_change_pct{{ticker=\"{ticker}\"}} {pct}",
+        ticker = options.label(quote),
+        last = quote.last,
+        pct = options.pct(quote)
+    )
+}
@@ -0,0 +1,61 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::time::Duration;
+use tinytemplate::TinyTemplate;
+
+use crate::quote::Quote;
+
+/// The values available to a user-supplied `text_template` /
+/// `tooltip_template` string, e.g. `"{ticker} {last} ({pct}%)"`.
+#[derive(Serialize)]
+pub struct RenderContext {
+    pub ticker: String,
+    pub last: f64,
+    pub pct: f64,
+    /// `last` minus the percent-change baseline (`prev_close` if none was
+    /// resolved), e.g. `+1.23` -- the same move `pct` expresses as a
+    /// percentage, in the quote's own currency.
+    pub change: f64,
+    pub volume: String,
+    pub cache_age: String,
+}
+
+impl RenderContext {
+    /// `baseline` is the resolved `Options::percent_change_baseline`, if
+    /// any -- `None` computes `pct`/`change` against `quote.prev_close`, as
+    /// before this setting existed. `compact_number_threshold` mirrors
+    /// `Options::compact_number_threshold`, controlling how `volume` is
+    /// compacted.
+    pub fn new(
+        quote: &Quote,
+        cache_age: Option<Duration>,
+        baseline: Option<f64>,
+        compact_number_threshold: f64,
+    ) -> Self {
+        Self {
+            ticker: quote.ticker.clone(),
+            last: quote.last,
+            pct: match baseline {
+                Some(baseline) => quote.percent_change_against(baseline),
+                None => quote.percent_change(),
+            },
+            change: quote.last - baseline.unwrap_or(quote.prev_close),
+            volume: match quote.volume {
+                Some(volume) => crate::numfmt::compact(volume, compact_number_threshold),
+                None => "n/a".to_string(),
+            },
+            cache_age: match cache_age {
+                Some(age) => format!("{}s", age.as_secs()),
+                None => "0s".to_string(),
+            },
+        }
+    }
+}
+
+pub fn render(template: &str, context: &RenderContext) -> Result<String> {
+    let mut tt = TinyTemplate::new();
+    tt.add_template("output", template)
+        .context("parsing output template")?;
+    tt.render("output", context)
+        .context("rendering output template")
+}
@@ -0,0 +1,291 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::quote::Quote;
+
+use super::template::{self, RenderContext};
+use super::Options;
+
+/// Waybar's `class` field accepts either a plain string or a list of them,
+/// applying all of them to the module's CSS -- used here so extra classes
+/// (`"afterhours"`, a market status like `"market-closed"`) can ride
+/// alongside the usual up/down/paused/stale one without displacing it.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Class {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// The JSON shape waybar's `custom` module type expects on stdout.
+#[derive(Serialize)]
+struct WaybarOutput {
+    text: String,
+    tooltip: String,
+    class: Class,
+    alt: String,
+    percentage: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+}
+
+/// `"9h 12m"` / `"12m"` / `"<1m"` -- coarse enough for a tooltip line, not
+/// meant for anything more precise than "roughly how long until the next
+/// session change". The `<1m` case matters here specifically: without it,
+/// the last minute before an open/close would tick down to a bare "0m",
+/// which reads as "no wait at all" rather than "any second now".
+fn format_hm(d: Duration) -> String {
+    let minutes = d.as_secs() / 60;
+    let (hours, minutes) = (minutes / 60, minutes % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else if minutes > 0 {
+        format!("{minutes}m")
+    } else {
+        "<1m".to_string()
+    }
+}
+
+/// A 0-100 gauge for waybar's `percentage` field: where `last` sits within
+/// the day's low-high range when the provider reports one, else percent
+/// change mapped over a +/-5% band.
+fn percentage(pct: f64, quote: &Quote) -> u8 {
+    if let (Some(low), Some(high)) = (quote.day_low, quote.day_high) {
+        if high > low {
+            return (((quote.last - low) / (high - low)) * 100.0).clamp(0.0, 100.0) as u8;
+        }
+    }
+    (((pct + 5.0) / 10.0) * 100.0).clamp(0.0, 100.0) as u8
+}
+
+fn pango_color(class: &str) -> &'static str {
+    match class {
+        "wayup" | "up" => "#00ff00",
+        "critdown" | "down" => "#ff0000",
+        "afterhours" => "#888888",
+        _ => "#ffffff",
+    }
+}
+
+/// `pango_color`, or a continuous gradient (see `super::gradient_color`) in
+/// place of the discrete up/down colors when `color_gradient` is on.
+/// `paused`/`stale`/`afterhours` don't have a meaningful position on the
+/// gradient, so they always keep their fixed color.
+fn resolved_color(color_class: &str, pct: f64, options: &Options) -> String {
+    if options.color_gradient
+        && matches!(color_class, "critdown" | "down" | "flat" | "up" | "wayup")
+    {
+        super::gradient_color(pct, options.thresholds)
+    } else {
+        pango_color(color_class).to_string()
+    }
+}
+
+/// The price/change to display and whether it's an extended-hours print:
+/// `quote.after_hours` when `show_after_hours` is on and the provider sent
+/// one, else the regular session's `last`/`percent_change` (against
+/// `percent_change_baseline`, or `prev_close` if unset).
+fn effective_quote(quote: &Quote, options: &Options) -> (f64, f64, bool) {
+    if options.show_after_hours {
+        if let Some(after_hours) = quote.after_hours {
+            return (after_hours, quote.after_hours_change().unwrap_or(0.0), true);
+        }
+    }
+    (quote.last, options.pct(quote), false)
+}
+
+fn default_text(quote: &Quote, options: &Options, class: &str) -> String {
+    let (price, pct, after_hours) = effective_quote(quote, options);
+    let price = options.price(price);
+    let label = options.labeled(quote);
+    let color_class = if after_hours { "afterhours" } else { class };
+    let spark = match &options.sparkline {
+        Some(spark) => format!(" {spark}"),
+        None => String::new(),
+    };
+    let glyph = match options.glyph(class) {
+        Some(glyph) => format!("{glyph} "),
+        None => String::new(),
+    };
+    let change = if after_hours {
+        quote.after_hours.unwrap_or(quote.last) - quote.last
+    } else {
+        options.change(quote)
+    };
+    let pct_field = if options.show_absolute_change {
+        format!("{}, {}%", options.signed(change), options.signed(pct))
+    } else {
+        format!("{}%", options.signed(pct))
+    };
+    let spread = match (options.show_spread, quote.bid, quote.ask) {
+        (true, Some(bid), Some(ask)) => {
+            format!(" {}/{}", options.price(bid), options.price(ask))
+        }
+        _ => String::new(),
+    };
+    // Pango's `<b>`/`<span ...>` tags aren't rendered as text, so the visible
+    // width is the same whether or not `options.pango` is set -- measure the
+    // plain form even when returning the markup one.
+    let visible = format!("{glyph}{label} {price} ({pct_field}){spark}{spread}");
+    let pad = pad_to_width(visible.chars().count(), options.text_width);
+    if options.pango {
+        format!(
+            "{glyph}<b>{label}</b> {price} <span color='{}'>({pct_field})</span>{spark}{spread}{pad}",
+            resolved_color(color_class, pct, options)
+        )
+    } else {
+        format!("{visible}{pad}")
+    }
+}
+
+/// The trailing spaces needed to pad a `visible_len`-character string out to
+/// `width` (see `Config::text_width`), or none if it's already at least that
+/// long or no width was configured.
+fn pad_to_width(visible_len: usize, width: Option<usize>) -> String {
+    let width = width.unwrap_or(0);
+    " ".repeat(width.saturating_sub(visible_len))
+}
+
+fn default_tooltip(quote: &Quote, options: &Options, cache_age: Duration, stale: bool) -> String {
+    let (price, _, after_hours) = effective_quote(quote, options);
+    let mut tooltip = format!("{}\nlast: {}", options.labeled(quote), options.price(price));
+    if after_hours {
+        tooltip.push_str(&format!("\nregular close: {}", options.price(quote.last)));
+    }
+    tooltip.push_str(&format!(
+        "\nprev close: {}",
+        options.price(quote.prev_close)
+    ));
+    if let Some(open) = quote.open {
+        tooltip.push_str(&format!("\nopen: {}", options.price(open)));
+    }
+    if let (Some(bid), Some(ask)) = (quote.bid, quote.ask) {
+        tooltip.push_str(&format!(
+            "\nbid/ask: {} / {}",
+            options.price(bid),
+            options.price(ask)
+        ));
+    }
+    if let (Some(low), Some(high)) = (quote.day_low, quote.day_high) {
+        let position = if high > low {
+            ((quote.last - low) / (high - low) * 100.0).clamp(0.0, 100.0)
+        } else {
+            50.0
+        };
+        tooltip.push_str(&format!(
+            "\nRange: {} - {} ({position:.0}%)",
+            options.price(low),
+            options.price(high)
+        ));
+    }
+    if let Some((low, high)) = options.week52_range {
+        let position = if high > low {
+            ((quote.last - low) / (high - low) * 100.0).clamp(0.0, 100.0)
+        } else {
+            50.0
+        };
+        tooltip.push_str(&format!(
+            "\n52w range: {} - {} ({position:.0}%)",
+            options.price(low),
+            options.price(high)
+        ));
+    }
+    if let Some(market_cap) = options.market_cap {
+        let (symbol, suffix) = options.currency;
+        let compact = crate::numfmt::compact(market_cap, options.compact_number_threshold);
+        tooltip.push_str(&format!(
+            "\nMarket cap: {}",
+            if suffix {
+                format!("{compact} {symbol}")
+            } else {
+                format!("{symbol}{compact}")
+            }
+        ));
+    }
+    if let Some(last_trade_time) = &quote.last_trade_time {
+        tooltip.push_str(&format!("\nlast trade: {last_trade_time}"));
+    }
+    if let Some(source) = &quote.source {
+        tooltip.push_str(&format!("\nsource: {source}"));
+    }
+    if stale {
+        tooltip.push_str(&format!(
+            "\nstale: refreshing ({}s old)",
+            cache_age.as_secs()
+        ));
+    }
+    if let Some((status, change)) = options.market_status {
+        let verb = if status.heading_to_open() {
+            "opens"
+        } else {
+            "closes"
+        };
+        tooltip.push_str(&format!(
+            "\nMarket: {}, {verb} in {}",
+            status.label(),
+            format_hm(change)
+        ));
+    }
+    if let Some(summary) = &options.watchlist_summary {
+        tooltip.push_str(&format!("\n\n{summary}"));
+    }
+    tooltip
+}
+
+pub fn render(quote: &Quote, options: &Options, cache_age: Duration) -> Result<String> {
+    let pct = options.pct(quote);
+    let class = super::class_with_hysteresis(
+        pct,
+        options.thresholds,
+        options.paused,
+        options.stale,
+        options.previous_class.as_deref(),
+    );
+    let (_, _, after_hours) = effective_quote(quote, options);
+    let context = RenderContext::new(
+        quote,
+        Some(cache_age),
+        options.percent_change_baseline,
+        options.compact_number_threshold,
+    );
+
+    let text = match options.text_template {
+        Some(tpl) => template::render(tpl, &context)?,
+        None => default_text(quote, options, class),
+    };
+    let tooltip = match options.tooltip_template {
+        Some(tpl) => template::render(tpl, &context)?,
+        None => default_tooltip(quote, options, cache_age, options.stale),
+    };
+
+    let display_class = super::display_class(class, options.class_names);
+    let mut classes = vec![display_class.to_string()];
+    if after_hours {
+        classes.push("afterhours".to_string());
+    }
+    if let Some((status, _)) = options.market_status {
+        classes.push(format!("market-{}", status.label()));
+    }
+    if super::is_critical(pct, options.thresholds) {
+        classes.push("critical".to_string());
+    }
+    let class_field = if classes.len() == 1 {
+        Class::One(classes.remove(0))
+    } else {
+        Class::Many(classes)
+    };
+    let color = options
+        .color_gradient
+        .then(|| resolved_color(class, pct, options));
+    let out = WaybarOutput {
+        text,
+        tooltip,
+        class: class_field,
+        alt: display_class.to_string(),
+        percentage: percentage(pct, quote),
+        color,
+    };
+    Ok(serde_json::to_string(&out).expect("waybar output is always serializable"))
+}
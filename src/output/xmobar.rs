@@ -0,0 +1,44 @@
+use crate::quote::Quote;
+
+use super::Options;
+
+fn color_for(class: &str) -> &'static str {
+    match class {
+        "wayup" | "up" => "green",
+        "critdown" | "down" => "red",
+        _ => "white",
+    }
+}
+
+/// xmobar's inline `<fc>` markup, colored by the same threshold class as the
+/// other formatters.
+pub fn render(quote: &Quote, options: &Options) -> String {
+    let pct = options.pct(quote);
+    let class = super::class_with_hysteresis(
+        pct,
+        options.thresholds,
+        options.paused,
+        options.stale,
+        options.previous_class.as_deref(),
+    );
+    let color = color_for(class);
+    let prec = options.precision;
+    let glyph = match options.glyph(class) {
+        Some(glyph) => format!("{glyph} "),
+        None => String::new(),
+    };
+    let pct_field = if options.show_absolute_change {
+        format!(
+            "{}, {}%",
+            options.signed(options.change(quote)),
+            options.signed(pct)
+        )
+    } else {
+        format!("{}%", options.signed(pct))
+    };
+    format!(
+        "<fc={color}>{glyph}{} {} ({pct_field})</fc>",
+        options.labeled(quote),
+        options.number_format.format(quote.last, prec, false),
+    )
+}
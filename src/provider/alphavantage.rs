@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::quote::Quote;
+
+use super::QuoteProvider;
+
+const BASE_URL: &str = "https://www.alphavantage.co/query";
+
+pub struct AlphaVantageProvider {
+    api_key: String,
+}
+
+impl AlphaVantageProvider {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GlobalQuoteResponse {
+    #[serde(rename = "Global Quote")]
+    global_quote: GlobalQuote,
+}
+
+#[derive(Debug, Deserialize)]
+struct GlobalQuote {
+    #[serde(rename = "02. open")]
+    open: String,
+    #[serde(rename = "05. price")]
+    price: String,
+    #[serde(rename = "08. previous close")]
+    previous_close: String,
+}
+
+impl QuoteProvider for AlphaVantageProvider {
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote> {
+        let url = format!(
+            "{BASE_URL}?function=GLOBAL_QUOTE&symbol={ticker}&apikey={}",
+            self.api_key
+        );
+        let response: GlobalQuoteResponse = ureq::get(&url)
+            .call()
+            .context("requesting Alpha Vantage quote")?
+            .body_mut()
+            .read_json()
+            .context("parsing Alpha Vantage response")?;
+        let last: f64 = response
+            .global_quote
+            .price
+            .parse()
+            .context("parsing Alpha Vantage price")?;
+        let prev_close: f64 = response
+            .global_quote
+            .previous_close
+            .parse()
+            .context("parsing Alpha Vantage previous close")?;
+        let open: f64 = response
+            .global_quote
+            .open
+            .parse()
+            .context("parsing Alpha Vantage open")?;
+        Ok(Quote {
+            ticker: ticker.to_string(),
+            last,
+            prev_close,
+            after_hours: None,
+            open: Some(open),
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: Some("alphavantage".to_string()),
+        })
+    }
+}
@@ -0,0 +1,57 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::quote::Quote;
+
+use super::QuoteProvider;
+
+const BASE_URL: &str = "https://api.binance.com/api/v3/ticker/24hr";
+
+/// Binance's public 24hr ticker endpoint. No API key or account needed, so
+/// crypto pairs routed here don't consume any paid quota.
+pub struct BinanceProvider;
+
+#[derive(Debug, Deserialize)]
+struct Ticker24hr {
+    #[serde(rename = "lastPrice")]
+    last_price: String,
+    #[serde(rename = "prevClosePrice")]
+    prev_close_price: String,
+}
+
+impl QuoteProvider for BinanceProvider {
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote> {
+        let url = format!("{BASE_URL}?symbol={}", ticker.to_uppercase());
+        let response: Ticker24hr = ureq::get(&url)
+            .call()
+            .context("requesting Binance 24hr ticker")?
+            .body_mut()
+            .read_json()
+            .context("parsing Binance response")?;
+        let last: f64 = response
+            .last_price
+            .parse()
+            .context("parsing Binance last price")?;
+        let prev_close: f64 = response
+            .prev_close_price
+            .parse()
+            .context("parsing Binance previous close price")?;
+        Ok(Quote {
+            ticker: ticker.to_string(),
+            last,
+            prev_close,
+            after_hours: None,
+            // Crypto trades continuously, so there's no session open
+            // distinct from the rolling 24hr window `prev_close` already
+            // reflects.
+            open: None,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: Some("binance".to_string()),
+        })
+    }
+}
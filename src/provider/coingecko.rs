@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::quote::Quote;
+
+use super::QuoteProvider;
+
+const BASE_URL: &str = "https://api.coingecko.com/api/v3/simple/price";
+
+/// CoinGecko's simple price API. Coins are addressed by their CoinGecko id
+/// (e.g. `bitcoin`, `ethereum`), not their ticker symbol.
+pub struct CoinGeckoProvider;
+
+#[derive(Debug, Deserialize)]
+struct SimplePrice {
+    usd: f64,
+    #[serde(rename = "usd_24h_change")]
+    usd_24h_change: f64,
+}
+
+impl QuoteProvider for CoinGeckoProvider {
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote> {
+        let url = format!("{BASE_URL}?ids={ticker}&vs_currencies=usd&include_24hr_change=true");
+        let response: HashMap<String, SimplePrice> = ureq::get(&url)
+            .call()
+            .context("requesting CoinGecko simple price")?
+            .body_mut()
+            .read_json()
+            .context("parsing CoinGecko response")?;
+        let price = response
+            .get(ticker)
+            .with_context(|| format!("no CoinGecko price returned for {ticker}"))?;
+        // CoinGecko gives us last price and its 24h percent change directly,
+        // so back out a synthetic previous close for the shared change math.
+        let prev_close = price.usd / (1.0 + price.usd_24h_change / 100.0);
+        Ok(Quote {
+            ticker: ticker.to_string(),
+            last: price.usd,
+            prev_close,
+            after_hours: None,
+            // Crypto trades continuously; there's no session open distinct
+            // from the synthetic `prev_close` above.
+            open: None,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: Some("coingecko".to_string()),
+        })
+    }
+}
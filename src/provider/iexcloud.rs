@@ -0,0 +1,61 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::quote::Quote;
+
+use super::QuoteProvider;
+
+const PRODUCTION_URL: &str = "https://cloud.iexapis.com/stable";
+const SANDBOX_URL: &str = "https://sandbox.iexapis.com/stable";
+
+pub struct IexCloudProvider {
+    api_key: String,
+    base_url: &'static str,
+}
+
+impl IexCloudProvider {
+    pub fn new(api_key: String, sandbox: bool) -> Self {
+        let base_url = if sandbox { SANDBOX_URL } else { PRODUCTION_URL };
+        Self { api_key, base_url }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct IexQuote {
+    #[serde(rename = "latestPrice")]
+    latest_price: f64,
+    #[serde(rename = "previousClose")]
+    previous_close: f64,
+    /// `null` before the market opens for the day.
+    #[serde(default)]
+    open: Option<f64>,
+}
+
+impl QuoteProvider for IexCloudProvider {
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote> {
+        let url = format!(
+            "{}/stock/{ticker}/quote?token={}",
+            self.base_url, self.api_key
+        );
+        let quote: IexQuote = ureq::get(&url)
+            .call()
+            .context("requesting IEX Cloud quote")?
+            .body_mut()
+            .read_json()
+            .context("parsing IEX Cloud response")?;
+        Ok(Quote {
+            ticker: ticker.to_string(),
+            last: quote.latest_price,
+            prev_close: quote.previous_close,
+            after_hours: None,
+            open: quote.open,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: Some("iexcloud".to_string()),
+        })
+    }
+}
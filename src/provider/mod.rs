@@ -0,0 +1,120 @@
+mod alphavantage;
+mod binance;
+mod coingecko;
+mod iexcloud;
+mod polygon;
+mod stooq;
+mod tiingo;
+mod twelvedata;
+mod yahoo;
+
+pub use alphavantage::AlphaVantageProvider;
+pub use binance::BinanceProvider;
+pub use coingecko::CoinGeckoProvider;
+pub use iexcloud::IexCloudProvider;
+pub use polygon::PolygonProvider;
+pub use stooq::StooqProvider;
+pub use tiingo::{
+    Tiingo52WeekProvider, TiingoEodProvider, TiingoFundamentalsProvider, TiingoFxProvider,
+    TiingoProvider,
+};
+pub use twelvedata::TwelveDataProvider;
+pub use yahoo::YahooProvider;
+
+use anyhow::{Context, Result};
+
+use crate::quote::Quote;
+
+/// The result of a conditional fetch (see [`QuoteProvider::fetch_quote_conditional`]).
+pub enum ConditionalQuote {
+    /// The upstream returned a full quote, with its response ETag if it sent
+    /// one, to be stored alongside the cached quote for next time.
+    Fresh(Box<Quote>, Option<String>),
+    /// The upstream confirmed (HTTP 304) that nothing has changed since the
+    /// ETag the caller sent; the caller's existing cached quote still holds.
+    NotModified,
+}
+
+/// A source of live (or last-known) quote data.
+///
+/// Implementations are responsible for talking to whatever upstream API they
+/// wrap and returning a normalized [`Quote`]. `fetch_quote` performs network
+/// I/O and should not itself apply caching -- that's handled by the caller.
+pub trait QuoteProvider {
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote>;
+
+    /// Fetches quotes for several tickers at once, for providers whose API
+    /// takes a batch of symbols in a single request. The default just calls
+    /// [`Self::fetch_quote`] once per ticker; override this for providers
+    /// that support real batching (like Tiingo's IEX endpoint) so callers
+    /// priming several tickers' caches at once don't pay for N round trips.
+    fn fetch_quotes(&self, tickers: &[&str]) -> Result<Vec<Quote>> {
+        tickers
+            .iter()
+            .map(|ticker| self.fetch_quote(ticker))
+            .collect()
+    }
+
+    /// Fetches `ticker`, sending `etag` as an `If-None-Match` conditional GET
+    /// when the caller already has one cached. The default ignores `etag`
+    /// and always does a full fetch; override this for providers that expose
+    /// response ETags (like Tiingo) so refreshing an unchanged after-hours
+    /// quote costs a 304 instead of a full response body.
+    fn fetch_quote_conditional(
+        &self,
+        ticker: &str,
+        etag: Option<&str>,
+    ) -> Result<ConditionalQuote> {
+        let _ = etag;
+        Ok(ConditionalQuote::Fresh(
+            Box::new(self.fetch_quote(ticker)?),
+            None,
+        ))
+    }
+}
+
+/// Constructs the configured provider by name.
+///
+/// `api_key` is only required by providers that need one; those that don't
+/// (like Yahoo) ignore it.
+pub fn from_name(name: &str, api_key: Option<&str>) -> Result<Box<dyn QuoteProvider>> {
+    match name {
+        "tiingo" => Ok(Box::new(TiingoProvider::new(require_key(name, api_key)?))),
+        "alphavantage" => Ok(Box::new(AlphaVantageProvider::new(require_key(
+            name, api_key,
+        )?))),
+        "yahoo" => Ok(Box::new(YahooProvider)),
+        "polygon" => Ok(Box::new(PolygonProvider::new(require_key(name, api_key)?))),
+        "coingecko" => Ok(Box::new(CoinGeckoProvider)),
+        "binance" => Ok(Box::new(BinanceProvider)),
+        "tiingo-fx" => Ok(Box::new(TiingoFxProvider::new(require_key(name, api_key)?))),
+        "tiingo-eod" => Ok(Box::new(TiingoEodProvider::new(require_key(
+            name, api_key,
+        )?))),
+        "tiingo-52w" => Ok(Box::new(Tiingo52WeekProvider::new(require_key(
+            name, api_key,
+        )?))),
+        "tiingo-mcap" => Ok(Box::new(TiingoFundamentalsProvider::new(require_key(
+            name, api_key,
+        )?))),
+        "stooq" => Ok(Box::new(StooqProvider)),
+        "twelvedata" => Ok(Box::new(TwelveDataProvider::new(require_key(
+            name, api_key,
+        )?))),
+        "iexcloud" => Ok(Box::new(IexCloudProvider::new(
+            require_key(name, api_key)?,
+            false,
+        ))),
+        "iexcloud-sandbox" => Ok(Box::new(IexCloudProvider::new(
+            require_key(name, api_key)?,
+            true,
+        ))),
+        other => anyhow::bail!("unknown provider: {other}"),
+    }
+}
+
+fn require_key(provider: &str, api_key: Option<&str>) -> Result<String> {
+    api_key
+        .map(str::to_string)
+        .with_context(|| format!("provider \"{provider}\" requires an api_key"))
+}
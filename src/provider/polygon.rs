@@ -0,0 +1,133 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::quote::Quote;
+
+use super::QuoteProvider;
+
+const BASE_URL: &str = "https://api.polygon.io";
+
+pub struct PolygonProvider {
+    api_key: String,
+}
+
+impl PolygonProvider {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key }
+    }
+
+    /// Real-time last trade + previous close, available on paid plans.
+    fn fetch_snapshot(&self, ticker: &str) -> Result<Quote> {
+        let url = format!(
+            "{BASE_URL}/v2/snapshot/locale/us/markets/stocks/tickers/{ticker}?apiKey={}",
+            self.api_key
+        );
+        let response: SnapshotResponse = ureq::get(&url)
+            .call()
+            .context("requesting Polygon snapshot")?
+            .body_mut()
+            .read_json()
+            .context("parsing Polygon snapshot response")?;
+        Ok(Quote {
+            ticker: ticker.to_string(),
+            last: response.ticker.last_trade.price,
+            prev_close: response.ticker.prev_day.close,
+            after_hours: None,
+            open: response.ticker.day.open,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: Some("polygon".to_string()),
+        })
+    }
+
+    /// Previous-day aggregate bar, available even on delayed/free plans.
+    fn fetch_previous_close(&self, ticker: &str) -> Result<Quote> {
+        let url = format!(
+            "{BASE_URL}/v2/aggs/ticker/{ticker}/prev?apiKey={}",
+            self.api_key
+        );
+        let response: PrevCloseResponse = ureq::get(&url)
+            .call()
+            .context("requesting Polygon previous close")?
+            .body_mut()
+            .read_json()
+            .context("parsing Polygon previous close response")?;
+        let bar = response
+            .results
+            .into_iter()
+            .next()
+            .with_context(|| format!("no Polygon aggregate returned for {ticker}"))?;
+        Ok(Quote {
+            ticker: ticker.to_string(),
+            last: bar.close,
+            prev_close: bar.close,
+            after_hours: None,
+            open: None,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: Some("polygon".to_string()),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SnapshotResponse {
+    ticker: SnapshotTicker,
+}
+
+#[derive(Debug, Deserialize)]
+struct SnapshotTicker {
+    #[serde(rename = "lastTrade")]
+    last_trade: LastTrade,
+    #[serde(rename = "prevDay")]
+    prev_day: PrevDay,
+    day: Day,
+}
+
+#[derive(Debug, Deserialize)]
+struct LastTrade {
+    #[serde(rename = "p")]
+    price: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PrevDay {
+    #[serde(rename = "c")]
+    close: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Day {
+    #[serde(rename = "o", default)]
+    open: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PrevCloseResponse {
+    results: Vec<AggBar>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AggBar {
+    #[serde(rename = "c")]
+    close: f64,
+}
+
+impl QuoteProvider for PolygonProvider {
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote> {
+        match self.fetch_snapshot(ticker) {
+            Ok(quote) => Ok(quote),
+            Err(_) => self
+                .fetch_previous_close(ticker)
+                .context("Polygon snapshot unavailable, and previous-close fallback also failed"),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+
+use crate::quote::Quote;
+
+use super::QuoteProvider;
+
+const BASE_URL: &str = "https://stooq.com/q/l/";
+
+/// Stooq's free CSV quote endpoint. Needs no API key, making it a reasonable
+/// default when a user hasn't configured one at all.
+pub struct StooqProvider;
+
+impl QuoteProvider for StooqProvider {
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote> {
+        let url = format!("{BASE_URL}?s={ticker}&f=sd2t2ohlcv&h&e=csv");
+        let body = ureq::get(&url)
+            .call()
+            .context("requesting Stooq CSV quote")?
+            .body_mut()
+            .read_to_string()
+            .context("reading Stooq CSV response")?;
+        parse_csv(ticker, &body)
+    }
+}
+
+fn parse_csv(ticker: &str, csv: &str) -> Result<Quote> {
+    // Header: Symbol,Date,Time,Open,High,Low,Close,Volume
+    let line = csv
+        .lines()
+        .nth(1)
+        .with_context(|| format!("no Stooq CSV row returned for {ticker}"))?;
+    let fields: Vec<&str> = line.split(',').collect();
+    let open: f64 = fields
+        .get(3)
+        .context("missing Stooq open field")?
+        .parse()
+        .context("parsing Stooq open field")?;
+    let close: f64 = fields
+        .get(6)
+        .context("missing Stooq close field")?
+        .parse()
+        .context("parsing Stooq close field")?;
+    Ok(Quote {
+        ticker: ticker.to_string(),
+        last: close,
+        prev_close: open,
+        after_hours: None,
+        // `open` is already spent above as our best-effort `prev_close`
+        // proxy (Stooq's free endpoint doesn't give a real one), so there's
+        // no separate value left to report as the session's actual open.
+        open: None,
+        volume: None,
+        day_high: None,
+        day_low: None,
+        bid: None,
+        ask: None,
+        last_trade_time: None,
+        source: Some("stooq".to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_stooq_csv_row() {
+        let csv = "Symbol,Date,Time,Open,High,Low,Close,Volume\naapl.us,2024-01-02,16:00:00,185.0,186.0,184.0,185.5,1000\n";
+        let quote = parse_csv("aapl.us", csv).unwrap();
+        assert_eq!(quote.last, 185.5);
+        assert_eq!(quote.prev_close, 185.0);
+    }
+}
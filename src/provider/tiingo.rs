@@ -0,0 +1,382 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::quote::Quote;
+
+use super::{ConditionalQuote, QuoteProvider};
+
+const IEX_URL: &str = "https://api.tiingo.com/iex";
+const FX_URL: &str = "https://api.tiingo.com/tiingo/fx";
+const EOD_URL: &str = "https://api.tiingo.com/tiingo/daily";
+const FUNDAMENTALS_URL: &str = "https://api.tiingo.com/tiingo/fundamentals";
+
+pub struct TiingoProvider {
+    api_key: String,
+}
+
+impl TiingoProvider {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TiingoIexQuote {
+    ticker: String,
+    last: f64,
+    #[serde(rename = "prevClose")]
+    prev_close: f64,
+    /// The IEX extended-hours print, absent when the ticker hasn't traded
+    /// outside regular hours yet (or at all, e.g. right after the open).
+    #[serde(rename = "afterHoursLast", default)]
+    after_hours_last: Option<f64>,
+    /// Absent before the exchange opens for the day.
+    #[serde(default)]
+    open: Option<f64>,
+    /// The regular session's traded volume so far.
+    #[serde(default)]
+    volume: Option<f64>,
+    /// The regular session's high print so far.
+    #[serde(default)]
+    high: Option<f64>,
+    /// The regular session's low print so far.
+    #[serde(default)]
+    low: Option<f64>,
+    /// The current best bid, absent outside regular trading hours.
+    #[serde(rename = "bidPrice", default)]
+    bid_price: Option<f64>,
+    /// The current best ask, absent outside regular trading hours.
+    #[serde(rename = "askPrice", default)]
+    ask_price: Option<f64>,
+    /// RFC 3339 timestamp of the last trade this quote reflects.
+    #[serde(default)]
+    timestamp: Option<String>,
+}
+
+impl QuoteProvider for TiingoProvider {
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote> {
+        self.fetch_quotes(&[ticker])?
+            .into_iter()
+            .next()
+            .with_context(|| format!("no Tiingo quote returned for {ticker}"))
+    }
+
+    /// The IEX endpoint accepts a comma-separated `tickers` list and returns
+    /// a quote per symbol in one response, so priming a whole watchlist's
+    /// cache costs one request instead of one per ticker.
+    fn fetch_quotes(&self, tickers: &[&str]) -> Result<Vec<Quote>> {
+        if tickers.is_empty() {
+            return Ok(Vec::new());
+        }
+        let url = format!(
+            "{IEX_URL}?tickers={}&token={}",
+            tickers.join(","),
+            self.api_key
+        );
+        let body: Vec<TiingoIexQuote> = ureq::get(&url)
+            .call()
+            .context("requesting Tiingo IEX quotes")?
+            .body_mut()
+            .read_json()
+            .context("parsing Tiingo IEX response")?;
+        Ok(body
+            .into_iter()
+            .map(|quote| Quote {
+                ticker: quote.ticker,
+                last: quote.last,
+                prev_close: quote.prev_close,
+                after_hours: quote.after_hours_last,
+                open: quote.open,
+                volume: quote.volume,
+                day_high: quote.high,
+                day_low: quote.low,
+                bid: quote.bid_price,
+                ask: quote.ask_price,
+                last_trade_time: quote.timestamp,
+                source: Some("tiingo".to_string()),
+            })
+            .collect())
+    }
+
+    /// Tiingo's IEX endpoint sends an `ETag` per response, so a refresh of a
+    /// quote that hasn't moved (common after-hours) can be a conditional GET
+    /// that comes back 304 with no body, instead of parsing the same numbers
+    /// again over the wire.
+    fn fetch_quote_conditional(
+        &self,
+        ticker: &str,
+        etag: Option<&str>,
+    ) -> Result<ConditionalQuote> {
+        let url = format!("{IEX_URL}?tickers={ticker}&token={}", self.api_key);
+        let mut request = ureq::get(&url);
+        if let Some(etag) = etag {
+            request = request.header("If-None-Match", etag);
+        }
+        let mut response = request.call().context("requesting Tiingo IEX quote")?;
+        if response.status() == ureq::http::StatusCode::NOT_MODIFIED {
+            return Ok(ConditionalQuote::NotModified);
+        }
+        let response_etag = response
+            .headers()
+            .get(ureq::http::header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body: Vec<TiingoIexQuote> = response
+            .body_mut()
+            .read_json()
+            .context("parsing Tiingo IEX response")?;
+        let quote = body
+            .into_iter()
+            .next()
+            .with_context(|| format!("no Tiingo quote returned for {ticker}"))?;
+        Ok(ConditionalQuote::Fresh(
+            Box::new(Quote {
+                ticker: quote.ticker,
+                last: quote.last,
+                prev_close: quote.prev_close,
+                after_hours: quote.after_hours_last,
+                open: quote.open,
+                volume: quote.volume,
+                day_high: quote.high,
+                day_low: quote.low,
+                bid: quote.bid_price,
+                ask: quote.ask_price,
+                last_trade_time: quote.timestamp,
+                source: Some("tiingo".to_string()),
+            }),
+            response_etag,
+        ))
+    }
+}
+
+/// Tiingo's forex endpoint, for pairs like `eurusd`. Prior mid close stands
+/// in for the previous close IEX quotes, since forex has no trading halt.
+pub struct TiingoFxProvider {
+    api_key: String,
+}
+
+impl TiingoFxProvider {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TiingoFxQuote {
+    #[serde(rename = "midPrice")]
+    mid_price: f64,
+    #[serde(rename = "prevMidPrice")]
+    prev_mid_price: f64,
+}
+
+impl QuoteProvider for TiingoFxProvider {
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote> {
+        let url = format!("{FX_URL}/{ticker}/top?token={}", self.api_key);
+        let body: Vec<TiingoFxQuote> = ureq::get(&url)
+            .call()
+            .context("requesting Tiingo FX quote")?
+            .body_mut()
+            .read_json()
+            .context("parsing Tiingo FX response")?;
+        let quote = body
+            .into_iter()
+            .next()
+            .with_context(|| format!("no Tiingo FX quote returned for {ticker}"))?;
+        Ok(Quote {
+            ticker: ticker.to_string(),
+            last: quote.mid_price,
+            prev_close: quote.prev_mid_price,
+            after_hours: None,
+            // Forex trades continuously, so there's no session open
+            // distinct from `prev_mid_price`.
+            open: None,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: Some("tiingo-fx".to_string()),
+        })
+    }
+}
+
+/// Tiingo's end-of-day endpoint, used for mutual funds like `VTSAX` that
+/// only ever publish a once-daily NAV. There's nothing intraday to refresh.
+pub struct TiingoEodProvider {
+    api_key: String,
+}
+
+impl TiingoEodProvider {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TiingoEodBar {
+    close: f64,
+}
+
+impl QuoteProvider for TiingoEodProvider {
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote> {
+        let url = format!(
+            "{EOD_URL}/{ticker}/prices?token={}&sort=-date&limit=2",
+            self.api_key
+        );
+        let bars: Vec<TiingoEodBar> = ureq::get(&url)
+            .call()
+            .context("requesting Tiingo EOD prices")?
+            .body_mut()
+            .read_json()
+            .context("parsing Tiingo EOD response")?;
+        let last = bars
+            .first()
+            .with_context(|| format!("no Tiingo EOD NAV returned for {ticker}"))?
+            .close;
+        // Funds that just started trading may not have a prior NAV yet.
+        let prev_close = bars.get(1).map_or(last, |bar| bar.close);
+        Ok(Quote {
+            ticker: ticker.to_string(),
+            last,
+            prev_close,
+            after_hours: None,
+            // A once-daily NAV has no separate intraday open to report.
+            open: None,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: Some("tiingo-eod".to_string()),
+        })
+    }
+}
+
+/// Tiingo's end-of-day endpoint again, but scanning a year of daily bars for
+/// their high/low rather than the latest close -- used to answer "where does
+/// today's price sit in the 52-week range" without a dedicated fundamentals
+/// subscription.
+pub struct Tiingo52WeekProvider {
+    api_key: String,
+}
+
+impl Tiingo52WeekProvider {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TiingoDailyBar {
+    #[serde(default)]
+    high: Option<f64>,
+    #[serde(default)]
+    low: Option<f64>,
+}
+
+impl QuoteProvider for Tiingo52WeekProvider {
+    /// Returns a `Quote` whose `last`/`prev_close` are repurposed as the
+    /// 52-week high/low (the same trick `TiingoFxQuote` plays with mid
+    /// prices) rather than a literal last-trade/previous-close pair.
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote> {
+        let url = format!(
+            "{EOD_URL}/{ticker}/prices?token={}&sort=-date&limit=252",
+            self.api_key
+        );
+        let bars: Vec<TiingoDailyBar> = ureq::get(&url)
+            .call()
+            .context("requesting Tiingo daily prices")?
+            .body_mut()
+            .read_json()
+            .context("parsing Tiingo daily prices response")?;
+        let high = bars
+            .iter()
+            .filter_map(|bar| bar.high)
+            .fold(f64::MIN, f64::max);
+        let low = bars
+            .iter()
+            .filter_map(|bar| bar.low)
+            .fold(f64::MAX, f64::min);
+        anyhow::ensure!(
+            high.is_finite() && low.is_finite(),
+            "no Tiingo daily high/low returned for {ticker}"
+        );
+        Ok(Quote {
+            ticker: ticker.to_string(),
+            last: high,
+            prev_close: low,
+            after_hours: None,
+            open: None,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: Some("tiingo-52w".to_string()),
+        })
+    }
+}
+
+/// Tiingo's fundamentals daily endpoint (a paid add-on), which reports
+/// `marketCap` alongside a handful of other per-day fundamentals. Used only
+/// to answer "what's this company worth right now" for the tooltip; the
+/// rest of the payload is ignored.
+pub struct TiingoFundamentalsProvider {
+    api_key: String,
+}
+
+impl TiingoFundamentalsProvider {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TiingoFundamentalsDaily {
+    #[serde(rename = "marketCap", default)]
+    market_cap: Option<f64>,
+}
+
+impl QuoteProvider for TiingoFundamentalsProvider {
+    /// Returns a `Quote` whose `last`/`prev_close` are repurposed as
+    /// today's/yesterday's market cap (the same trick `Tiingo52WeekProvider`
+    /// plays with high/low), so the usual cache/TTL machinery and
+    /// `percent_change` work unmodified even though this isn't really a
+    /// tradeable quote.
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote> {
+        let url = format!(
+            "{FUNDAMENTALS_URL}/{ticker}/daily?token={}&sort=-date&limit=2",
+            self.api_key
+        );
+        let days: Vec<TiingoFundamentalsDaily> = ureq::get(&url)
+            .call()
+            .context("requesting Tiingo fundamentals")?
+            .body_mut()
+            .read_json()
+            .context("parsing Tiingo fundamentals response")?;
+        let last = days
+            .first()
+            .and_then(|day| day.market_cap)
+            .with_context(|| format!("no Tiingo market cap returned for {ticker}"))?;
+        // A ticker with only one day of fundamentals history yet has no
+        // prior cap to compare against.
+        let prev_close = days.get(1).and_then(|day| day.market_cap).unwrap_or(last);
+        Ok(Quote {
+            ticker: ticker.to_string(),
+            last,
+            prev_close,
+            after_hours: None,
+            open: None,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: Some("tiingo-fundamentals".to_string()),
+        })
+    }
+}
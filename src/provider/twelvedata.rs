@@ -0,0 +1,64 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::quote::Quote;
+
+use super::QuoteProvider;
+
+const BASE_URL: &str = "https://api.twelvedata.com/quote";
+
+/// Twelve Data covers stocks, FX, and crypto through one API, which keeps
+/// mixed watchlists simple for users who already hold a key there.
+pub struct TwelveDataProvider {
+    api_key: String,
+}
+
+impl TwelveDataProvider {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TwelveDataQuote {
+    close: String,
+    previous_close: String,
+    #[serde(default)]
+    open: Option<String>,
+}
+
+impl QuoteProvider for TwelveDataProvider {
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote> {
+        let url = format!("{BASE_URL}?symbol={ticker}&apikey={}", self.api_key);
+        let response: TwelveDataQuote = ureq::get(&url)
+            .call()
+            .context("requesting Twelve Data quote")?
+            .body_mut()
+            .read_json()
+            .context("parsing Twelve Data response")?;
+        Ok(Quote {
+            ticker: ticker.to_string(),
+            last: response
+                .close
+                .parse()
+                .context("parsing Twelve Data close")?,
+            prev_close: response
+                .previous_close
+                .parse()
+                .context("parsing Twelve Data previous close")?,
+            after_hours: None,
+            open: response
+                .open
+                .map(|open| open.parse())
+                .transpose()
+                .context("parsing Twelve Data open")?,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: Some("twelvedata".to_string()),
+        })
+    }
+}
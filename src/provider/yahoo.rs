@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::quote::Quote;
+
+use super::QuoteProvider;
+
+const BASE_URL: &str = "https://query1.finance.yahoo.com/v8/finance/chart";
+
+/// Yahoo Finance's undocumented chart API. Unlike the other backends this
+/// needs no API key, which makes it a reasonable zero-configuration default.
+pub struct YahooProvider;
+
+#[derive(Debug, Deserialize)]
+struct ChartResponse {
+    chart: Chart,
+}
+
+#[derive(Debug, Deserialize)]
+struct Chart {
+    result: Vec<ChartResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChartResult {
+    meta: ChartMeta,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChartMeta {
+    #[serde(rename = "regularMarketPrice")]
+    regular_market_price: f64,
+    #[serde(rename = "previousClose")]
+    previous_close: f64,
+    /// Absent before today's open, e.g. premarket on a ticker Yahoo hasn't
+    /// started a new session for yet.
+    #[serde(rename = "regularMarketOpen", default)]
+    regular_market_open: Option<f64>,
+}
+
+impl QuoteProvider for YahooProvider {
+    fn fetch_quote(&self, ticker: &str) -> Result<Quote> {
+        let url = format!("{BASE_URL}/{ticker}");
+        let response: ChartResponse = ureq::get(&url)
+            .call()
+            .context("requesting Yahoo Finance chart")?
+            .body_mut()
+            .read_json()
+            .context("parsing Yahoo Finance response")?;
+        let meta = response
+            .chart
+            .result
+            .into_iter()
+            .next()
+            .with_context(|| format!("no Yahoo Finance result returned for {ticker}"))?
+            .meta;
+        Ok(Quote {
+            ticker: ticker.to_string(),
+            last: meta.regular_market_price,
+            prev_close: meta.previous_close,
+            after_hours: None,
+            open: meta.regular_market_open,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: Some("yahoo".to_string()),
+        })
+    }
+}
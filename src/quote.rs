@@ -0,0 +1,180 @@
+/// A single price observation for a ticker, normalized across providers.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Quote {
+    pub ticker: String,
+    pub last: f64,
+    pub prev_close: f64,
+    /// The most recent extended-hours print, if the provider publishes one
+    /// (currently just Tiingo's IEX endpoint). `None` means "this provider
+    /// has no extended-hours data", not "the market is closed" -- a ticker
+    /// can be after-hours with no trades yet to report.
+    #[serde(default)]
+    pub after_hours: Option<f64>,
+    /// The current session's opening print, if the provider reports one.
+    /// `None` either means the provider doesn't surface it, or the market
+    /// hasn't opened yet today -- either way, callers wanting an `open`
+    /// baseline (see `Config::percent_change_baseline`) should fall back to
+    /// `prev_close`.
+    #[serde(default)]
+    pub open: Option<f64>,
+    /// The regular session's traded volume so far, if the provider reports
+    /// one. `None` for providers (or asset classes, like forex) that don't
+    /// surface it. See `crate::numfmt::compact` for how it's displayed.
+    #[serde(default)]
+    pub volume: Option<f64>,
+    /// The regular session's high print so far, if the provider reports one.
+    /// `None` for providers that don't surface an intraday range.
+    #[serde(default)]
+    pub day_high: Option<f64>,
+    /// The regular session's low print so far, if the provider reports one.
+    #[serde(default)]
+    pub day_low: Option<f64>,
+    /// The current best bid, if the provider reports one. `None` for
+    /// providers that only publish trade prints, not a live quote.
+    #[serde(default)]
+    pub bid: Option<f64>,
+    /// The current best ask, if the provider reports one. See `bid`.
+    #[serde(default)]
+    pub ask: Option<f64>,
+    /// The exchange timestamp of the last trade, as reported by the
+    /// provider (usually RFC 3339), if it reports one -- e.g. Tiingo's IEX
+    /// endpoint's `timestamp`. `None` for providers that only report a
+    /// price with no trade time attached.
+    #[serde(default)]
+    pub last_trade_time: Option<String>,
+    /// Which provider this quote came from (e.g. `"tiingo"`), for judging
+    /// how fresh or trustworthy a quote is. Set by whichever provider
+    /// fetched it; `None` for a quote read back over D-Bus (which only
+    /// carries `(last, prev_close)`) and for cache rows written before this
+    /// field existed.
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+impl Quote {
+    pub fn percent_change(&self) -> f64 {
+        self.percent_change_against(self.prev_close)
+    }
+
+    /// Percent change against an arbitrary reference price instead of
+    /// `prev_close`, e.g. `open` or a per-ticker reference price set via
+    /// `Config::percent_change_baseline` -- the shared math `percent_change`
+    /// itself uses.
+    pub fn percent_change_against(&self, baseline: f64) -> f64 {
+        if baseline == 0.0 {
+            return 0.0;
+        }
+        (self.last - baseline) / baseline * 100.0
+    }
+
+    /// Percent change of `after_hours` versus the regular-session `last`,
+    /// i.e. how far the extended-hours print has moved since the close it's
+    /// riding on top of -- `None` if this provider didn't report one.
+    pub fn after_hours_change(&self) -> Option<f64> {
+        let after_hours = self.after_hours?;
+        if self.last == 0.0 {
+            return Some(0.0);
+        }
+        Some((after_hours - self.last) / self.last * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_change_up() {
+        let q = Quote {
+            ticker: "AAPL".into(),
+            last: 110.0,
+            prev_close: 100.0,
+            after_hours: None,
+            open: None,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: None,
+        };
+        assert!((q.percent_change() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn percent_change_zero_prev_close() {
+        let q = Quote {
+            ticker: "AAPL".into(),
+            last: 110.0,
+            prev_close: 0.0,
+            after_hours: None,
+            open: None,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: None,
+        };
+        assert_eq!(q.percent_change(), 0.0);
+    }
+
+    #[test]
+    fn after_hours_change_up() {
+        let q = Quote {
+            ticker: "AAPL".into(),
+            last: 110.0,
+            prev_close: 100.0,
+            after_hours: Some(112.2),
+            open: None,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: None,
+        };
+        assert!((q.after_hours_change().unwrap() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn after_hours_change_absent() {
+        let q = Quote {
+            ticker: "AAPL".into(),
+            last: 110.0,
+            prev_close: 100.0,
+            after_hours: None,
+            open: None,
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: None,
+        };
+        assert_eq!(q.after_hours_change(), None);
+    }
+
+    #[test]
+    fn percent_change_against_a_custom_baseline() {
+        let q = Quote {
+            ticker: "AAPL".into(),
+            last: 110.0,
+            prev_close: 100.0,
+            after_hours: None,
+            open: Some(105.0),
+            volume: None,
+            day_high: None,
+            day_low: None,
+            bid: None,
+            ask: None,
+            last_trade_time: None,
+            source: None,
+        };
+        assert!((q.percent_change_against(q.open.unwrap()) - 4.761_904_761_904_762).abs() < 1e-9);
+    }
+}
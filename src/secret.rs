@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use secret_service::blocking::SecretService;
+use secret_service::EncryptionType;
+
+/// Looks up an API key stored in the Freedesktop Secret Service (GNOME
+/// Keyring, KWallet) under the attributes `service` / `key` parsed out of an
+/// `id` like `"stocker/tiingo"` (service `stocker`, key `tiingo`).
+pub fn lookup(id: &str) -> Result<String> {
+    let (service, key) = id.split_once('/').unwrap_or(("stocker", id));
+
+    let ss =
+        SecretService::connect(EncryptionType::Dh).context("connecting to the Secret Service")?;
+    let collection = ss
+        .get_default_collection()
+        .context("opening the default Secret Service collection")?;
+    collection
+        .ensure_unlocked()
+        .context("unlocking the default Secret Service collection")?;
+
+    let mut attributes = HashMap::new();
+    attributes.insert("service", service);
+    attributes.insert("key", key);
+
+    let items = collection
+        .search_items(attributes)
+        .with_context(|| format!("searching the Secret Service for {id}"))?;
+    let item = items
+        .first()
+        .with_context(|| format!("no Secret Service entry found for {id}"))?;
+    let secret = item
+        .get_secret()
+        .with_context(|| format!("reading the secret for {id}"))?;
+    String::from_utf8(secret).with_context(|| format!("secret for {id} wasn't valid UTF-8"))
+}
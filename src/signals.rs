@@ -0,0 +1,30 @@
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use anyhow::{Context, Result};
+use signal_hook::consts::{SIGUSR1, SIGUSR2};
+use signal_hook::iterator::Signals;
+
+use crate::ipc::DaemonState;
+
+/// Starts a background thread that turns `SIGUSR1` (force refresh) and
+/// `SIGUSR2` (advance to the next ticker) into the same daemon state changes
+/// a `stocker ctl` command would make, so a sway keybinding can
+/// `pkill -USR1 stocker` for an instant update instead of waiting out the
+/// cache TTL.
+pub fn watch(state: Arc<Mutex<DaemonState>>, condvar: Arc<Condvar>) -> Result<()> {
+    let mut signals = Signals::new([SIGUSR1, SIGUSR2]).context("registering signal handlers")?;
+    thread::spawn(move || {
+        for signal in signals.forever() {
+            let mut state = state.lock().unwrap();
+            match signal {
+                SIGUSR1 => state.force_refresh = true,
+                SIGUSR2 => state.index = state.index.wrapping_add(1),
+                _ => {}
+            }
+            state.wake = true;
+            condvar.notify_one();
+        }
+    });
+    Ok(())
+}
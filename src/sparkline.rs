@@ -0,0 +1,58 @@
+//! Renders a run of price samples as a compact block-character sparkline
+//! (e.g. `▂▃▅▇▆`), so a quote carries a sense of the day's trend rather than
+//! a single point-in-time number. See `cache::history_since` for where the
+//! samples themselves come from.
+
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One character per sample, scaled between `values`' own min and max.
+/// Returns an empty string for fewer than two samples, since a single point
+/// has no trend to show. A flat series (every sample equal) renders as a
+/// straight middle-height line rather than dividing by zero.
+pub fn render(values: &[f64]) -> String {
+    if values.len() < 2 {
+        return String::new();
+    }
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+    values
+        .iter()
+        .map(|&value| {
+            let level = if range == 0.0 {
+                LEVELS.len() / 2
+            } else {
+                (((value - min) / range) * (LEVELS.len() - 1) as f64).round() as usize
+            };
+            LEVELS[level.min(LEVELS.len() - 1)]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_one_character_per_sample() {
+        assert_eq!(render(&[1.0, 2.0, 3.0]).chars().count(), 3);
+    }
+
+    #[test]
+    fn spans_the_full_range_of_levels() {
+        let spark = render(&[1.0, 2.0, 3.0]);
+        assert_eq!(spark.chars().next(), Some('▁'));
+        assert_eq!(spark.chars().last(), Some('█'));
+    }
+
+    #[test]
+    fn flat_series_uses_the_middle_level() {
+        assert_eq!(render(&[5.0, 5.0, 5.0]), "▅▅▅");
+    }
+
+    #[test]
+    fn fewer_than_two_samples_renders_nothing() {
+        assert_eq!(render(&[]), "");
+        assert_eq!(render(&[1.0]), "");
+    }
+}
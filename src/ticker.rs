@@ -0,0 +1,87 @@
+/// Splits a ticker entry like `crypto:bitcoin` into the provider it should be
+/// routed to and the symbol to pass that provider, falling back to the
+/// configured default provider when there's no recognized prefix.
+pub fn resolve(entry: &str) -> (Option<&'static str>, &str) {
+    // Tiingo IEX doesn't serve index levels; Yahoo's chart API does, so
+    // `^`-prefixed symbols like `^GSPC` are routed there transparently.
+    if entry.starts_with('^') {
+        return (Some("yahoo"), entry);
+    }
+    match entry.split_once(':') {
+        Some(("crypto", symbol)) => (Some("coingecko"), symbol),
+        Some(("binance", symbol)) => (Some("binance"), symbol),
+        Some(("fx", symbol)) => (Some("tiingo-fx"), symbol),
+        Some(("fund", symbol)) => (Some("tiingo-eod"), symbol),
+        Some(("52w", symbol)) => (Some("tiingo-52w"), symbol),
+        Some(("mcap", symbol)) => (Some("tiingo-mcap"), symbol),
+        _ => (None, entry),
+    }
+}
+
+/// Whether `entry` names a fund whose NAV is only ever published once a day,
+/// a synthetic 52-week-range lookup, or a synthetic market-cap lookup, so
+/// there's no point refreshing it on the usual intraday cadence.
+pub fn is_daily_only(entry: &str) -> bool {
+    entry.starts_with("fund:") || entry.starts_with("52w:") || entry.starts_with("mcap:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crypto_prefix_routes_to_coingecko() {
+        assert_eq!(resolve("crypto:bitcoin"), (Some("coingecko"), "bitcoin"));
+    }
+
+    #[test]
+    fn binance_prefix_routes_to_binance() {
+        assert_eq!(resolve("binance:BTCUSDT"), (Some("binance"), "BTCUSDT"));
+    }
+
+    #[test]
+    fn fx_prefix_routes_to_tiingo_fx() {
+        assert_eq!(resolve("fx:eurusd"), (Some("tiingo-fx"), "eurusd"));
+    }
+
+    #[test]
+    fn index_symbol_routes_to_yahoo() {
+        assert_eq!(resolve("^GSPC"), (Some("yahoo"), "^GSPC"));
+    }
+
+    #[test]
+    fn fund_prefix_routes_to_tiingo_eod() {
+        assert_eq!(resolve("fund:VTSAX"), (Some("tiingo-eod"), "VTSAX"));
+    }
+
+    #[test]
+    fn fund_prefix_is_daily_only() {
+        assert!(is_daily_only("fund:VTSAX"));
+        assert!(!is_daily_only("AAPL"));
+    }
+
+    #[test]
+    fn week52_prefix_routes_to_tiingo_52w() {
+        assert_eq!(resolve("52w:AAPL"), (Some("tiingo-52w"), "AAPL"));
+    }
+
+    #[test]
+    fn week52_prefix_is_daily_only() {
+        assert!(is_daily_only("52w:AAPL"));
+    }
+
+    #[test]
+    fn mcap_prefix_routes_to_tiingo_mcap() {
+        assert_eq!(resolve("mcap:AAPL"), (Some("tiingo-mcap"), "AAPL"));
+    }
+
+    #[test]
+    fn mcap_prefix_is_daily_only() {
+        assert!(is_daily_only("mcap:AAPL"));
+    }
+
+    #[test]
+    fn plain_symbol_has_no_override() {
+        assert_eq!(resolve("AAPL"), (None, "AAPL"));
+    }
+}
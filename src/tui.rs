@@ -0,0 +1,106 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::Constraint;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::{Block, Borders, Row, Table};
+use ratatui::Terminal;
+
+use crate::config::Thresholds;
+use crate::output;
+use crate::quote::Quote;
+
+/// How often the watchlist table re-fetches quotes while the TUI is open.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+fn class_color(class: &str) -> Color {
+    match class {
+        "wayup" | "up" => Color::Green,
+        "critdown" | "down" => Color::Red,
+        _ => Color::White,
+    }
+}
+
+fn rows(quotes: &[Quote]) -> Vec<Row<'static>> {
+    quotes
+        .iter()
+        .map(|quote| {
+            // The watchlist shows every configured ticker at once, so it uses
+            // the default thresholds rather than per-ticker overrides.
+            let pct = quote.percent_change();
+            let class = output::class(pct, Thresholds::default(), false, false);
+            Row::new(vec![
+                quote.ticker.clone(),
+                format!("{:.2}", quote.last),
+                format!("{:+.2}%", pct),
+            ])
+            .style(Style::default().fg(class_color(class)))
+        })
+        .collect()
+}
+
+/// Runs the `stocker tui` watchlist view: a full-screen table of every
+/// configured ticker, colored by the same threshold classes as the bar
+/// formatters, refreshed periodically until the user presses `q`.
+pub fn run(fetch_all: impl Fn() -> Result<Vec<Quote>>) -> Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = run_loop(&mut terminal, fetch_all);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+fn run_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    fetch_all: impl Fn() -> Result<Vec<Quote>>,
+) -> Result<()> {
+    let mut quotes = fetch_all()?;
+    let mut last_refresh = Instant::now();
+
+    loop {
+        terminal.draw(|frame| {
+            let widths = [
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(10),
+            ];
+            let table = Table::new(rows(&quotes), widths)
+                .header(Row::new(vec!["Ticker", "Last", "Change"]))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("stocker watchlist (q to quit)"),
+                );
+            frame.render_widget(table, frame.area());
+        })?;
+
+        let timeout = REFRESH_INTERVAL.saturating_sub(last_refresh.elapsed());
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    return Ok(());
+                }
+            }
+        }
+
+        if last_refresh.elapsed() >= REFRESH_INTERVAL {
+            quotes = fetch_all()?;
+            last_refresh = Instant::now();
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use std::thread;
+
+use sd_notify::NotifyState;
+
+/// Tells systemd the daemon is ready to serve, so a `Type=notify` unit's
+/// `ExecStart` unblocks and any `After=`/`Wants=` units can start.
+pub fn notify_ready() {
+    let _ = sd_notify::notify(&[NotifyState::Ready]);
+}
+
+/// If `WATCHDOG_USEC` is set (a `Type=notify` unit with `WatchdogSec=`),
+/// starts a background thread pinging `WATCHDOG=1` at half that interval, so
+/// systemd restarts the daemon if it ever stops responding. Does nothing
+/// outside a watchdog-enabled unit.
+pub fn watch() {
+    let Some(interval) = sd_notify::watchdog_enabled() else {
+        return;
+    };
+    thread::spawn(move || loop {
+        thread::sleep(interval / 2);
+        let _ = sd_notify::notify(&[NotifyState::Watchdog]);
+    });
+}